@@ -1,2 +1,3 @@
-pub mod powered;
-pub mod unpowered;
+mod macros;
+pub mod powered;
+pub mod unpowered;
@@ -0,0 +1,154 @@
+// Builds a powered::BehaviorTreeDef from a nested description:
+//
+//     bt! {
+//         sequence {
+//             leaf(MyNode::Aim)
+//             repeat(3) { gas_cost(2) { leaf(MyNode::Attack) } }
+//             wrapper(MyWrapper::Retry) { leaf(MyNode::Reload) }
+//         }
+//     }
+//
+// Composites (sequence, selector, wrapper) take any number of children, optionally
+// separated by commas. Decorators (repeat, repeat_until_success, repeat_until_fail,
+// succeeder, failer, inverter, gas_cost, gas_budget) take exactly one, and anything else
+// is a compile error.
+#[macro_export]
+macro_rules! bt {
+    ($($node:tt)*) => {
+        $crate::__bt_node!([$crate::powered::BehaviorTreeDef] $($node)*)
+    };
+}
+
+// Builds an unpowered::UnpoweredTreeDef, like bt!. Executor is available, gas nodes aren't.
+#[macro_export]
+macro_rules! unpowered_bt {
+    ($($node:tt)*) => {
+        $crate::__bt_node!([$crate::unpowered::UnpoweredTreeDef] $($node)*)
+    };
+}
+
+// Expands exactly one node, given the path of the definition type.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bt_node {
+    ([$($def:tt)*] leaf($node:expr) $(,)?) => {
+        $($def)*::User($node)
+    };
+    ([$($def:tt)*] sequence { $($children:tt)* } $(,)?) => {
+        $($def)*::Sequence($crate::__bt_children!([$($def)*] [] $($children)*))
+    };
+    ([$($def:tt)*] selector { $($children:tt)* } $(,)?) => {
+        $($def)*::Selector($crate::__bt_children!([$($def)*] [] $($children)*))
+    };
+    ([$($def:tt)*] executor { $($children:tt)* } $(,)?) => {
+        $($def)*::Executor($crate::__bt_children!([$($def)*] [] $($children)*))
+    };
+    ([$($def:tt)*] wrapper($wrapper:expr) { $($children:tt)* } $(,)?) => {
+        $($def)*::Wrapper($wrapper, $crate::__bt_children!([$($def)*] [] $($children)*))
+    };
+    ([$($def:tt)*] repeat($repeats:expr) { $($child:tt)* } $(,)?) => {
+        $($def)*::Repeat($crate::__bt_only_child!([$($def)*] "repeat" $($child)*), $repeats)
+    };
+    ([$($def:tt)*] repeat_until_success { $($child:tt)* } $(,)?) => {
+        $($def)*::RepeatUntilSuccess($crate::__bt_only_child!(
+            [$($def)*] "repeat_until_success" $($child)*
+        ))
+    };
+    ([$($def:tt)*] repeat_until_fail { $($child:tt)* } $(,)?) => {
+        $($def)*::RepeatUntilFail($crate::__bt_only_child!(
+            [$($def)*] "repeat_until_fail" $($child)*
+        ))
+    };
+    ([$($def:tt)*] succeeder { $($child:tt)* } $(,)?) => {
+        $($def)*::Succeeder($crate::__bt_only_child!([$($def)*] "succeeder" $($child)*))
+    };
+    ([$($def:tt)*] failer { $($child:tt)* } $(,)?) => {
+        $($def)*::Failer($crate::__bt_only_child!([$($def)*] "failer" $($child)*))
+    };
+    ([$($def:tt)*] inverter { $($child:tt)* } $(,)?) => {
+        $($def)*::Inverter($crate::__bt_only_child!([$($def)*] "inverter" $($child)*))
+    };
+    ([$($def:tt)*] gas_cost($gas_cost:expr) { $($child:tt)* } $(,)?) => {
+        $($def)*::GasCost($crate::__bt_only_child!([$($def)*] "gas_cost" $($child)*), $gas_cost)
+    };
+    ([$($def:tt)*] gas_budget($allowance:expr) { $($child:tt)* } $(,)?) => {
+        $($def)*::GasBudget(
+            $crate::__bt_only_child!([$($def)*] "gas_budget" $($child)*),
+            $allowance,
+        )
+    };
+    ([$($def:tt)*] $($other:tt)*) => {
+        ::std::compile_error!(::std::concat!(
+            "expected exactly one node, found: ",
+            ::std::stringify!($($other)*)
+        ))
+    };
+}
+
+// Boxes a decorator's child, naming the decorator when it has none. Anything but exactly one
+// node is a compile error:
+//
+/// ```compile_fail
+/// let def = behavior_bark::bt! { repeat(2) { } };
+/// ```
+///
+/// ```compile_fail
+/// let def = behavior_bark::bt! { inverter { sequence { } sequence { } } };
+/// ```
+///
+/// ```
+/// use behavior_bark::powered::{BehaviorTreeDef, UserNodeDefinition, UserWrapperDefinition};
+///
+/// fn repeated<U: UserNodeDefinition, W: UserWrapperDefinition<U>>() -> BehaviorTreeDef<U, W> {
+///     behavior_bark::bt! { repeat(2) { inverter { sequence { } } } }
+/// }
+/// ```
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bt_only_child {
+    ([$($def:tt)*] $kind:literal) => {
+        ::std::compile_error!(::std::concat!($kind, " expects a child node, found none"))
+    };
+    ([$($def:tt)*] $kind:literal $($child:tt)+) => {
+        ::std::boxed::Box::new($crate::__bt_node!([$($def)*] $($child)+))
+    };
+}
+
+// Expands a composite's children into a Vec, one node at a time.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bt_children {
+    ([$($def:tt)*] [$($done:expr,)*]) => {
+        ::std::vec![$($done,)*]
+    };
+    ([$($def:tt)*] [$($done:expr,)*] , $($rest:tt)*) => {
+        $crate::__bt_children!([$($def)*] [$($done,)*] $($rest)*)
+    };
+    ([$($def:tt)*] [$($done:expr,)*] $kind:ident ($($args:tt)*) { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::__bt_children!(
+            [$($def)*]
+            [$($done,)* $crate::__bt_node!([$($def)*] $kind($($args)*) { $($inner)* }),]
+            $($rest)*
+        )
+    };
+    ([$($def:tt)*] [$($done:expr,)*] $kind:ident { $($inner:tt)* } $($rest:tt)*) => {
+        $crate::__bt_children!(
+            [$($def)*]
+            [$($done,)* $crate::__bt_node!([$($def)*] $kind { $($inner)* }),]
+            $($rest)*
+        )
+    };
+    ([$($def:tt)*] [$($done:expr,)*] $kind:ident ($($args:tt)*) $($rest:tt)*) => {
+        $crate::__bt_children!(
+            [$($def)*]
+            [$($done,)* $crate::__bt_node!([$($def)*] $kind($($args)*)),]
+            $($rest)*
+        )
+    };
+    ([$($def:tt)*] [$($done:expr,)*] $($other:tt)*) => {
+        ::std::compile_error!(::std::concat!(
+            "expected a node, found: ",
+            ::std::stringify!($($other)*)
+        ))
+    };
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+    charge_gas, get_bt_id, BehaviorTree, BehaviorTreeAudit, BehaviorTreeAuditTrait,
+    BehaviorTreeDef, BehaviorTreeNodeError, BehaviorTreePath, BehaviorTreeState,
+    BoxedBehaviorTreeFor, Gas, UserNodeDefinition, UserWrapperDefinition,
+};
+use crate::unpowered::{self, UnpoweredFunction, UnpoweredFunctionState, UnpoweredTreeDef};
+
+impl From<UnpoweredFunctionState> for BehaviorTreeState {
+    fn from(state: UnpoweredFunctionState) -> Self {
+        match state {
+            UnpoweredFunctionState::Waiting => BehaviorTreeState::Waiting,
+            UnpoweredFunctionState::Failed => BehaviorTreeState::Failed,
+            UnpoweredFunctionState::Complete => BehaviorTreeState::Complete,
+        }
+    }
+}
+
+// Runs an unpowered node inside a powered tree, charging a fixed gas cost each time it is
+// resumed and waiting for gas if the budget can't afford it.
+pub struct UnpoweredNode<M, C, G = Option<i32>> {
+    name: String,
+    node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+    gas_cost: u32,
+    _gas: PhantomData<fn(&mut G)>,
+}
+
+impl<M, C, G> UnpoweredNode<M, C, G> {
+    pub fn new(
+        node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+        gas_cost: u32,
+    ) -> Self {
+        UnpoweredNode {
+            name: get_bt_id(),
+            node,
+            gas_cost,
+            _gas: PhantomData,
+        }
+    }
+}
+
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for UnpoweredNode<M, C, G> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        gas: &mut G,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        audit.enter(self.get_name());
+        if !charge_gas(gas, self.gas_cost) {
+            audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+            return BehaviorTreeState::WaitingForGas;
+        }
+        let result = if audit.is_some() {
+            self.node.resume_with_audit(model, controller, audit)
+        } else {
+            self.node.resume_with(model, controller)
+        }
+        .into();
+        audit.exit(self.get_name(), result);
+        result
+    }
+
+    fn reset(self: &mut Self, model: &Self::Model) {
+        self.node.reset(model);
+    }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
+}
+
+// A powered leaf definition for an unpowered user node, so unpowered leaves can be reused
+// in powered definitions.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct UnpoweredUserNode<U, G = Option<i32>> {
+    pub node: U,
+    // Gas charged each time the node is resumed.
+    pub gas_cost: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _gas: PhantomData<fn(&mut G)>,
+}
+
+impl<U, G> UnpoweredUserNode<U, G> {
+    pub fn new(node: U, gas_cost: u32) -> Self {
+        UnpoweredUserNode {
+            node,
+            gas_cost,
+            _gas: PhantomData,
+        }
+    }
+}
+
+impl<U: unpowered::UserNodeDefinition, G: Gas + 'static> UserNodeDefinition
+    for UnpoweredUserNode<U, G>
+{
+    type Model = U::Model;
+    type Controller = U::Controller;
+    type Gas = G;
+
+    fn create_node(&self) -> BoxedBehaviorTreeFor<Self> {
+        Box::new(UnpoweredNode::new(self.node.create_node(), self.gas_cost))
+    }
+
+    fn try_create_node(&self) -> Result<BoxedBehaviorTreeFor<Self>, BehaviorTreeNodeError> {
+        let node = self.node.try_create_node()?;
+        Ok(Box::new(UnpoweredNode::new(node, self.gas_cost)))
+    }
+
+    fn describe(&self) -> String {
+        self.node.describe()
+    }
+}
+
+// A node with no equivalent in the other flavour of definition.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BehaviorTreeDefConversionError {
+    pub path: BehaviorTreePath,
+    pub kind: &'static str,
+}
+
+impl fmt::Display for BehaviorTreeDefConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {:?} has no equivalent in the other tree flavour",
+            self.kind, self.path
+        )
+    }
+}
+
+impl std::error::Error for BehaviorTreeDefConversionError {}
+
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    // Converts to an unpowered definition, mapping user and wrapper definitions.
+    // Fails on gas-only nodes (GasCost, GasBudget), which unpowered trees don't have.
+    pub fn to_unpowered<U2, W2>(
+        &self,
+        map_user: &impl Fn(&U) -> U2,
+        map_wrapper: &impl Fn(&W) -> W2,
+    ) -> Result<UnpoweredTreeDef<U2, W2>, BehaviorTreeDefConversionError>
+    where
+        U2: unpowered::UserNodeDefinition + Send + Sync,
+        W2: unpowered::UserWrapperDefinition<U2> + Send + Sync,
+    {
+        self.to_unpowered_at(map_user, map_wrapper, &mut Vec::new())
+    }
+
+    fn to_unpowered_at<U2, W2>(
+        &self,
+        map_user: &impl Fn(&U) -> U2,
+        map_wrapper: &impl Fn(&W) -> W2,
+        path: &mut BehaviorTreePath,
+    ) -> Result<UnpoweredTreeDef<U2, W2>, BehaviorTreeDefConversionError>
+    where
+        U2: unpowered::UserNodeDefinition + Send + Sync,
+        W2: unpowered::UserWrapperDefinition<U2> + Send + Sync,
+    {
+        let mut children = Vec::new();
+        for (child_index, child) in self.children().iter().enumerate() {
+            path.push(child_index);
+            children.push(child.to_unpowered_at(map_user, map_wrapper, path)?);
+            path.pop();
+        }
+        let mut only_child = || Box::new(children.pop().unwrap());
+        Ok(match self {
+            BehaviorTreeDef::Sequence(_) => UnpoweredTreeDef::Sequence(children),
+            BehaviorTreeDef::Selector(_) => UnpoweredTreeDef::Selector(children),
+            BehaviorTreeDef::Repeat(_, repeats) => UnpoweredTreeDef::Repeat(only_child(), *repeats),
+            BehaviorTreeDef::RepeatUntilSuccess(_) => {
+                UnpoweredTreeDef::RepeatUntilSuccess(only_child())
+            }
+            BehaviorTreeDef::RepeatUntilFail(_) => UnpoweredTreeDef::RepeatUntilFail(only_child()),
+            BehaviorTreeDef::Succeeder(_) => UnpoweredTreeDef::Succeeder(only_child()),
+            BehaviorTreeDef::Failer(_) => UnpoweredTreeDef::Failer(only_child()),
+            BehaviorTreeDef::Inverter(_) => UnpoweredTreeDef::Inverter(only_child()),
+            BehaviorTreeDef::User(node_def) => UnpoweredTreeDef::User(map_user(node_def)),
+            BehaviorTreeDef::Wrapper(wrapper_def, _) => {
+                UnpoweredTreeDef::Wrapper(map_wrapper(wrapper_def), children)
+            }
+            BehaviorTreeDef::GasCost(_, _) | BehaviorTreeDef::GasBudget(_, _) => {
+                return Err(BehaviorTreeDefConversionError {
+                    path: path.clone(),
+                    kind: self.kind_name(),
+                })
+            }
+        })
+    }
+}
+
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: unpowered::UserNodeDefinition + Send + Sync,
+    W: unpowered::UserWrapperDefinition<U> + Send + Sync,
+{
+    // Converts to a powered definition, mapping user and wrapper definitions.
+    // Fails on Executor nodes, which powered trees don't have.
+    pub fn to_powered<U2, W2>(
+        &self,
+        map_user: &impl Fn(&U) -> U2,
+        map_wrapper: &impl Fn(&W) -> W2,
+    ) -> Result<BehaviorTreeDef<U2, W2>, BehaviorTreeDefConversionError>
+    where
+        U2: UserNodeDefinition,
+        W2: UserWrapperDefinition<U2>,
+    {
+        self.to_powered_at(map_user, map_wrapper, &mut Vec::new())
+    }
+
+    fn to_powered_at<U2, W2>(
+        &self,
+        map_user: &impl Fn(&U) -> U2,
+        map_wrapper: &impl Fn(&W) -> W2,
+        path: &mut BehaviorTreePath,
+    ) -> Result<BehaviorTreeDef<U2, W2>, BehaviorTreeDefConversionError>
+    where
+        U2: UserNodeDefinition,
+        W2: UserWrapperDefinition<U2>,
+    {
+        let convert_all =
+            |node_defs: &Vec<UnpoweredTreeDef<U, W>>,
+             path: &mut BehaviorTreePath|
+             -> Result<Vec<BehaviorTreeDef<U2, W2>>, BehaviorTreeDefConversionError> {
+                let mut children = Vec::new();
+                for (child_index, child) in node_defs.iter().enumerate() {
+                    path.push(child_index);
+                    children.push(child.to_powered_at(map_user, map_wrapper, path)?);
+                    path.pop();
+                }
+                Ok(children)
+            };
+        let convert_one =
+            |node_def: &UnpoweredTreeDef<U, W>,
+             path: &mut BehaviorTreePath|
+             -> Result<Box<BehaviorTreeDef<U2, W2>>, BehaviorTreeDefConversionError> {
+                path.push(0);
+                let child = node_def.to_powered_at(map_user, map_wrapper, path)?;
+                path.pop();
+                Ok(Box::new(child))
+            };
+        Ok(match self {
+            UnpoweredTreeDef::Sequence(node_defs) => {
+                BehaviorTreeDef::Sequence(convert_all(node_defs, path)?)
+            }
+            UnpoweredTreeDef::Selector(node_defs) => {
+                BehaviorTreeDef::Selector(convert_all(node_defs, path)?)
+            }
+            UnpoweredTreeDef::Executor(_) => {
+                return Err(BehaviorTreeDefConversionError {
+                    path: path.clone(),
+                    kind: "Executor",
+                })
+            }
+            UnpoweredTreeDef::Repeat(node_def, repeats) => {
+                BehaviorTreeDef::Repeat(convert_one(node_def, path)?, *repeats)
+            }
+            UnpoweredTreeDef::RepeatUntilSuccess(node_def) => {
+                BehaviorTreeDef::RepeatUntilSuccess(convert_one(node_def, path)?)
+            }
+            UnpoweredTreeDef::RepeatUntilFail(node_def) => {
+                BehaviorTreeDef::RepeatUntilFail(convert_one(node_def, path)?)
+            }
+            UnpoweredTreeDef::Succeeder(node_def) => {
+                BehaviorTreeDef::Succeeder(convert_one(node_def, path)?)
+            }
+            UnpoweredTreeDef::Failer(node_def) => {
+                BehaviorTreeDef::Failer(convert_one(node_def, path)?)
+            }
+            UnpoweredTreeDef::Inverter(node_def) => {
+                BehaviorTreeDef::Inverter(convert_one(node_def, path)?)
+            }
+            UnpoweredTreeDef::User(node_def) => BehaviorTreeDef::User(map_user(node_def)),
+            UnpoweredTreeDef::Wrapper(wrapper_def, node_defs) => {
+                BehaviorTreeDef::Wrapper(map_wrapper(wrapper_def), convert_all(node_defs, path)?)
+            }
+        })
+    }
+}
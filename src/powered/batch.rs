@@ -0,0 +1,81 @@
+use std::thread;
+
+use super::{BehaviorTree, BehaviorTreeAudit, BehaviorTreeState};
+
+// One agent's part of a batch: its tree, what to resume it with, and its own gas and audit,
+// which are updated in place.
+pub struct BehaviorTreeBatchAgent<'a, M, C, G = Option<i32>> {
+    pub tree: &'a mut (dyn BehaviorTree<G, Model = M, Controller = C> + Send + Sync),
+    pub model: &'a M,
+    pub controller: &'a mut C,
+    pub gas: G,
+    pub audit: Option<BehaviorTreeAudit>,
+}
+
+impl<'a, M: 'static, C: 'static, G> BehaviorTreeBatchAgent<'a, M, C, G> {
+    pub fn new(
+        tree: &'a mut (dyn BehaviorTree<G, Model = M, Controller = C> + Send + Sync),
+        model: &'a M,
+        controller: &'a mut C,
+        gas: G,
+    ) -> Self {
+        BehaviorTreeBatchAgent {
+            tree,
+            model,
+            controller,
+            gas,
+            audit: None,
+        }
+    }
+
+    pub fn with_audit(mut self, audit: BehaviorTreeAudit) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    fn resume(&mut self) -> BehaviorTreeState {
+        self.tree
+            .resume_with(self.model, self.controller, &mut self.gas, &mut self.audit)
+    }
+}
+
+// Resumes every agent once, spread over up to the given number of threads.
+//
+// Agents are split into contiguous chunks, one per thread, and each agent only touches
+// its own tree, controller, gas and audit, so the results don't depend on how the threads
+// are scheduled. States are returned in the same order as the agents.
+pub fn tick_batch<M, C, G>(
+    agents: &mut [BehaviorTreeBatchAgent<'_, M, C, G>],
+    threads: usize,
+) -> Vec<BehaviorTreeState>
+where
+    M: Sync + 'static,
+    C: Send + 'static,
+    G: Send,
+{
+    let mut states = vec![BehaviorTreeState::Waiting; agents.len()];
+    if agents.is_empty() {
+        return states;
+    }
+    let threads = threads.clamp(1, agents.len());
+    if threads == 1 {
+        for (agent, state) in agents.iter_mut().zip(states.iter_mut()) {
+            *state = agent.resume();
+        }
+        return states;
+    }
+    let chunk_size = agents.len().div_ceil(threads);
+    thread::scope(|scope| {
+        for (agent_chunk, state_chunk) in agents
+            .chunks_mut(chunk_size)
+            .zip(states.chunks_mut(chunk_size))
+        {
+            scope.spawn(move || {
+                for (agent, state) in agent_chunk.iter_mut().zip(state_chunk.iter_mut()) {
+                    *state = agent.resume();
+                }
+            });
+        }
+    });
+    states
+}
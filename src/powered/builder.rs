@@ -0,0 +1,61 @@
+use super::{BehaviorTreeDef, UserNodeDefinition, UserWrapperDefinition};
+
+// Shorthand for building definitions in code. Composites take their children up front,
+// and decorators wrap the definition they are called on, so they can only ever have one
+// child:
+//
+//     BehaviorTreeDef::sequence([
+//         BehaviorTreeDef::user(Attack),
+//         BehaviorTreeDef::user(Reload).repeat(3).gas_cost(2),
+//     ])
+//
+// See also the bt! macro.
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    pub fn user(node_def: U) -> Self {
+        BehaviorTreeDef::User(node_def)
+    }
+
+    pub fn sequence(node_defs: impl IntoIterator<Item = Self>) -> Self {
+        BehaviorTreeDef::Sequence(node_defs.into_iter().collect())
+    }
+
+    pub fn selector(node_defs: impl IntoIterator<Item = Self>) -> Self {
+        BehaviorTreeDef::Selector(node_defs.into_iter().collect())
+    }
+
+    pub fn wrapper(wrapper_def: W, node_defs: impl IntoIterator<Item = Self>) -> Self {
+        BehaviorTreeDef::Wrapper(wrapper_def, node_defs.into_iter().collect())
+    }
+
+    pub fn repeat(self, repeats: usize) -> Self {
+        BehaviorTreeDef::Repeat(Box::new(self), repeats)
+    }
+
+    pub fn repeat_until_success(self) -> Self {
+        BehaviorTreeDef::RepeatUntilSuccess(Box::new(self))
+    }
+
+    pub fn repeat_until_fail(self) -> Self {
+        BehaviorTreeDef::RepeatUntilFail(Box::new(self))
+    }
+
+    pub fn succeeder(self) -> Self {
+        BehaviorTreeDef::Succeeder(Box::new(self))
+    }
+
+    pub fn failer(self) -> Self {
+        BehaviorTreeDef::Failer(Box::new(self))
+    }
+
+    pub fn inverter(self) -> Self {
+        BehaviorTreeDef::Inverter(Box::new(self))
+    }
+
+    pub fn gas_cost(self, gas_cost: u32) -> Self {
+        BehaviorTreeDef::GasCost(Box::new(self), gas_cost)
+    }
+
+    pub fn gas_budget(self, allowance: u32) -> Self {
+        BehaviorTreeDef::GasBudget(Box::new(self), allowance)
+    }
+}
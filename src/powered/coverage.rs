@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug};
+
+use super::{
+    get_bt_id, BehaviorTree, BehaviorTreeAudit, BehaviorTreeAuditTrait, BehaviorTreeDef,
+    BehaviorTreeIndex, BehaviorTreeMarker, BehaviorTreePath, BehaviorTreeState, BoxedBehaviorTree,
+    UserNodeDefinition, UserWrapperDefinition,
+};
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct BehaviorTreeNodeCoverage {
+    pub entries: u64,
+    pub complete: u64,
+    pub failed: u64,
+    pub waiting: u64,
+    pub waiting_for_gas: u64,
+}
+
+impl BehaviorTreeNodeCoverage {
+    pub fn outcomes(&self, state: BehaviorTreeState) -> u64 {
+        match state {
+            BehaviorTreeState::Complete => self.complete,
+            BehaviorTreeState::Failed => self.failed,
+            BehaviorTreeState::Waiting => self.waiting,
+            BehaviorTreeState::WaitingForGas => self.waiting_for_gas,
+        }
+    }
+}
+
+// Accumulates, by definition path, which nodes were entered and which outcomes they produced.
+// Audits from any number of ticks and agents sharing a definition can be recorded.
+//
+// Only nodes that audit themselves are seen. Built-in nodes do; user leaves and wrappers
+// that don't are covered in trees created with create_audited_tree, which audits them.
+#[derive(Clone, Default)]
+pub struct BehaviorTreeCoverage {
+    nodes: HashMap<BehaviorTreePath, BehaviorTreeNodeCoverage>,
+    // The paths of nodes that gave out no children, so wrappers hiding theirs can be told apart.
+    childless: HashSet<BehaviorTreePath>,
+}
+
+impl BehaviorTreeCoverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Records an audit of a tree, using the index created from that same tree.
+    pub fn record(&mut self, index: &BehaviorTreeIndex, audit: &BehaviorTreeAudit) {
+        self.childless.extend(index.childless().iter().cloned());
+        for (event, path) in audit.events().zip(index.trace(audit.events())) {
+            let Some(path) = path else {
+                continue;
+            };
+            let node = self.nodes.entry(path).or_default();
+            match event {
+                BehaviorTreeMarker::Enter(_) => node.entries += 1,
+                BehaviorTreeMarker::Exit(_, BehaviorTreeState::Complete) => node.complete += 1,
+                BehaviorTreeMarker::Exit(_, BehaviorTreeState::Failed) => node.failed += 1,
+                BehaviorTreeMarker::Exit(_, BehaviorTreeState::Waiting) => node.waiting += 1,
+                BehaviorTreeMarker::Exit(_, BehaviorTreeState::WaitingForGas) => {
+                    node.waiting_for_gas += 1
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &BehaviorTreeCoverage) {
+        for (path, other_node) in other.nodes.iter() {
+            let node = self.nodes.entry(path.clone()).or_default();
+            node.entries += other_node.entries;
+            node.complete += other_node.complete;
+            node.failed += other_node.failed;
+            node.waiting += other_node.waiting;
+            node.waiting_for_gas += other_node.waiting_for_gas;
+        }
+        self.childless.extend(other.childless.iter().cloned());
+    }
+
+    pub fn node(&self, path: &[usize]) -> BehaviorTreeNodeCoverage {
+        self.nodes.get(path).copied().unwrap_or_default()
+    }
+
+    pub fn report<'a, U, W>(
+        &'a self,
+        def: &'a BehaviorTreeDef<U, W>,
+    ) -> BehaviorTreeCoverageReport<'a, U, W>
+    where
+        U: UserNodeDefinition,
+        W: UserWrapperDefinition<U>,
+    {
+        BehaviorTreeCoverageReport {
+            coverage: self,
+            def,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum BehaviorTreeCoverageGap {
+    // The node was never entered.
+    Uncovered,
+    // The node was entered but never failed, e.g. a condition that always held.
+    NeverFailed,
+    // The node was entered but never completed.
+    NeverCompleted,
+}
+
+// A coverage summary laid over a definition. Displays as the definition's outline,
+// annotated with entry and outcome counts and any gaps.
+//
+// Wrappers that hide the nodes they wrap are opaque: they are covered as a whole, and the
+// nodes under them are left out of the gaps, the ratio and the outline.
+pub struct BehaviorTreeCoverageReport<'a, U: UserNodeDefinition, W: UserWrapperDefinition<U>> {
+    coverage: &'a BehaviorTreeCoverage,
+    def: &'a BehaviorTreeDef<U, W>,
+}
+
+impl<'a, U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeCoverageReport<'a, U, W> {
+    // Every definition node with a coverage gap, in definition order.
+    pub fn gaps(&self) -> Vec<(BehaviorTreePath, BehaviorTreeCoverageGap)> {
+        let mut gaps = Vec::new();
+        self.collect_gaps(self.def, &mut Vec::new(), &mut gaps);
+        gaps
+    }
+
+    // The fraction of definition nodes that were entered at least once.
+    pub fn ratio(&self) -> f32 {
+        let mut total = 0;
+        let mut entered = 0;
+        self.count(self.def, &mut Vec::new(), &mut total, &mut entered);
+        if total == 0 {
+            return 1.0;
+        }
+        entered as f32 / total as f32
+    }
+
+    fn is_opaque(&self, def: &BehaviorTreeDef<U, W>, path: &[usize]) -> bool {
+        !def.children().is_empty() && self.coverage.childless.contains(path)
+    }
+
+    fn gap(&self, path: &[usize]) -> Option<BehaviorTreeCoverageGap> {
+        let node = self.coverage.node(path);
+        if node.entries == 0 {
+            Some(BehaviorTreeCoverageGap::Uncovered)
+        } else if node.failed == 0 && node.complete > 0 {
+            Some(BehaviorTreeCoverageGap::NeverFailed)
+        } else if node.complete == 0 && node.failed > 0 {
+            Some(BehaviorTreeCoverageGap::NeverCompleted)
+        } else {
+            None
+        }
+    }
+
+    fn collect_gaps(
+        &self,
+        def: &BehaviorTreeDef<U, W>,
+        path: &mut BehaviorTreePath,
+        gaps: &mut Vec<(BehaviorTreePath, BehaviorTreeCoverageGap)>,
+    ) {
+        if let Some(gap) = self.gap(path) {
+            gaps.push((path.clone(), gap));
+        }
+        if self.is_opaque(def, path) {
+            return;
+        }
+        for (child_index, child) in def.children().iter().enumerate() {
+            path.push(child_index);
+            self.collect_gaps(child, path, gaps);
+            path.pop();
+        }
+    }
+
+    fn count(
+        &self,
+        def: &BehaviorTreeDef<U, W>,
+        path: &mut BehaviorTreePath,
+        total: &mut usize,
+        entered: &mut usize,
+    ) {
+        *total += 1;
+        if self.coverage.node(path).entries > 0 {
+            *entered += 1;
+        }
+        if self.is_opaque(def, path) {
+            return;
+        }
+        for (child_index, child) in def.children().iter().enumerate() {
+            path.push(child_index);
+            self.count(child, path, total, entered);
+            path.pop();
+        }
+    }
+}
+
+impl<'a, U, W> BehaviorTreeCoverageReport<'a, U, W>
+where
+    U: UserNodeDefinition + Debug,
+    W: UserWrapperDefinition<U> + Debug,
+{
+    fn write_node(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        def: &BehaviorTreeDef<U, W>,
+        path: &mut BehaviorTreePath,
+    ) -> fmt::Result {
+        write!(f, "{:indent$}", "", indent = path.len() * 2)?;
+        match def {
+            BehaviorTreeDef::Repeat(_, repeats) => write!(f, "Repeat({})", repeats)?,
+            BehaviorTreeDef::User(node_def) => write!(f, "User({:?})", node_def)?,
+            BehaviorTreeDef::Wrapper(wrapper_def, _) => write!(f, "Wrapper({:?})", wrapper_def)?,
+            _ => write!(f, "{}", def.kind_name())?,
+        }
+        let node = self.coverage.node(path);
+        write!(
+            f,
+            " [entered {}: complete {}, failed {}, waiting {}, waiting for gas {}]",
+            node.entries, node.complete, node.failed, node.waiting, node.waiting_for_gas
+        )?;
+        match self.gap(path) {
+            Some(BehaviorTreeCoverageGap::Uncovered) => write!(f, " UNCOVERED")?,
+            Some(BehaviorTreeCoverageGap::NeverFailed) => write!(f, " never failed")?,
+            Some(BehaviorTreeCoverageGap::NeverCompleted) => write!(f, " never completed")?,
+            None => {}
+        }
+        if self.is_opaque(def, path) {
+            return writeln!(f, " (opaque)");
+        }
+        writeln!(f)?;
+        for (child_index, child) in def.children().iter().enumerate() {
+            path.push(child_index);
+            self.write_node(f, child, path)?;
+            path.pop();
+        }
+        Ok(())
+    }
+}
+
+impl<'a, U, W> fmt::Display for BehaviorTreeCoverageReport<'a, U, W>
+where
+    U: UserNodeDefinition + Debug,
+    W: UserWrapperDefinition<U> + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_node(f, self.def, &mut Vec::new())?;
+        writeln!(f, "{:.1}% of nodes covered", self.ratio() * 100.0)
+    }
+}
+
+// Wraps a node, auditing its entries and outcomes under a name of its own, so leaves and
+// wrappers that don't audit themselves are still covered. The wrapped node's own name isn't
+// indexed: its own entries and exits are left out, and its markers go to the Audited node.
+pub struct Audited<M, C, G = Option<i32>> {
+    name: String,
+    node: BoxedBehaviorTree<G, M, C>,
+}
+
+impl<M, C, G> Audited<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>) -> Self {
+        Audited {
+            name: get_bt_id(),
+            node,
+        }
+    }
+}
+
+impl<M: 'static, C: 'static, G> BehaviorTree<G> for Audited<M, C, G> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        gas: &mut G,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        audit.enter(&self.name);
+        let result = self.node.resume_with(model, controller, gas, audit);
+        audit.exit(&self.name, result);
+        result
+    }
+
+    fn reset(self: &mut Self, model: &Self::Model) {
+        self.node.reset(model);
+    }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        self.node.children()
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        self.node.children_mut()
+    }
+}
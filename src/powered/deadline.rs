@@ -0,0 +1,137 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::Gas;
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+// The real monotonic clock.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// A clock that only moves when told to, for deterministic tests.
+// Clones share the same time, so a test can hold one and hand another to a Deadline.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            start: Instant::now(),
+            elapsed_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + Duration::from_nanos(self.elapsed_nanos.load(Ordering::SeqCst))
+    }
+}
+
+// A wall-clock budget: nodes may keep working until the deadline passes, after which
+// they wait for gas. Gas costs charged by built-in nodes are ignored, since time is spent
+// by doing the work, but every built-in node checks the deadline before running a child.
+// Carved allowances are in milliseconds, and time can't be refunded.
+#[derive(Debug, Clone)]
+pub struct Deadline<K: Clock = SystemClock> {
+    deadline: Instant,
+    // How long after it was made the deadline falls, for deadlines made with after.
+    duration: Option<Duration>,
+    clock: K,
+}
+
+impl Deadline<SystemClock> {
+    // A fixed instant, which stays the same when a runner resets to it each tick.
+    pub fn at(deadline: Instant) -> Self {
+        Self::with_clock(deadline, SystemClock)
+    }
+
+    // A time from now. A runner that resets to it each tick starts the time over every tick.
+    pub fn after(duration: Duration) -> Self {
+        Self::after_with_clock(duration, SystemClock)
+    }
+}
+
+impl<K: Clock> Deadline<K> {
+    pub fn with_clock(deadline: Instant, clock: K) -> Self {
+        Deadline {
+            deadline,
+            duration: None,
+            clock,
+        }
+    }
+
+    pub fn after_with_clock(duration: Duration, clock: K) -> Self {
+        Deadline {
+            deadline: clock.now() + duration,
+            duration: Some(duration),
+            clock,
+        }
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    pub fn time_left(&self) -> Duration {
+        self.deadline.saturating_duration_since(self.clock.now())
+    }
+}
+
+impl<K: Clock + Clone> Gas for Deadline<K> {
+    fn has_gas(&self) -> bool {
+        self.clock.now() < self.deadline
+    }
+
+    fn try_consume(&mut self, _cost: u32) -> bool {
+        self.has_gas()
+    }
+
+    // Milliseconds until the deadline.
+    fn remaining(&self) -> Option<f64> {
+        Some(self.time_left().as_secs_f64() * 1000.0)
+    }
+
+    fn carve(&mut self, allowance: u32) -> Self {
+        let deadline = self
+            .deadline
+            .min(self.clock.now() + Duration::from_millis(allowance as u64));
+        Deadline::with_clock(deadline, self.clock.clone())
+    }
+
+    fn refund(&mut self, _unspent: Self) {}
+
+    fn has_gas_for_free_nodes(&self) -> bool {
+        self.has_gas()
+    }
+
+    fn restart(&mut self) {
+        if let Some(duration) = self.duration {
+            self.deadline = self.clock.now() + duration;
+        }
+    }
+}
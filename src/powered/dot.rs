@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{
+    BehaviorTreeAudit, BehaviorTreeDef, BehaviorTreeIndex, BehaviorTreeMarker, BehaviorTreePath,
+    BehaviorTreeReplayFrame, BehaviorTreeState, UserNodeDefinition, UserWrapperDefinition,
+};
+
+// How user nodes and wrappers are labelled in diagrams.
+pub trait NodeLabel {
+    fn label(&self) -> String;
+}
+
+impl NodeLabel for () {
+    fn label(&self) -> String {
+        "()".to_string()
+    }
+}
+
+// Node outcomes to colour a diagram with, keyed by definition path.
+#[derive(Debug, Default, Clone)]
+pub struct BehaviorTreeDotOverlay {
+    outcomes: HashMap<BehaviorTreePath, BehaviorTreeState>,
+}
+
+impl BehaviorTreeDotOverlay {
+    // The last outcome of each node during the given tick, which must have been retained.
+    // The index must come from the tree that produced the audit.
+    pub fn for_tick(audit: &BehaviorTreeAudit, index: &BehaviorTreeIndex, tick: u64) -> Self {
+        let events = audit
+            .ticks()
+            .into_iter()
+            .find(|(events_tick, _)| *events_tick == Some(tick))
+            .map(|(_, events)| events)
+            .unwrap_or_default();
+        Self::for_events(events, index)
+    }
+
+    // The last outcome of each node over the whole audit.
+    pub fn for_audit(audit: &BehaviorTreeAudit, index: &BehaviorTreeIndex) -> Self {
+        Self::for_events(audit.events().collect(), index)
+    }
+
+    // The outcomes as of a replayed frame.
+    pub fn for_frame(frame: &BehaviorTreeReplayFrame) -> Self {
+        BehaviorTreeDotOverlay {
+            outcomes: frame.outcomes.clone(),
+        }
+    }
+
+    fn for_events(events: Vec<&BehaviorTreeMarker>, index: &BehaviorTreeIndex) -> Self {
+        let mut outcomes = HashMap::new();
+        let paths = index.trace(events.iter().copied());
+        for (event, path) in events.into_iter().zip(paths) {
+            if let (BehaviorTreeMarker::Exit(_, state), Some(path)) = (event, path) {
+                outcomes.insert(path, *state);
+            }
+        }
+        BehaviorTreeDotOverlay { outcomes }
+    }
+
+    pub fn outcome(&self, path: &[usize]) -> Option<BehaviorTreeState> {
+        self.outcomes.get(path).copied()
+    }
+}
+
+impl<U, W> BehaviorTreeDef<U, W>
+where
+    U: UserNodeDefinition + NodeLabel,
+    W: UserWrapperDefinition<U> + NodeLabel,
+{
+    // Renders the definition as a Graphviz DOT digraph, children left to right.
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with_overlay(&BehaviorTreeDotOverlay::default())
+    }
+
+    // Renders the definition with each node filled according to its outcome in the overlay:
+    // green for complete, red for failed, yellow for waiting and blue for waiting for gas.
+    pub fn to_dot_with_overlay(&self, overlay: &BehaviorTreeDotOverlay) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph behavior_tree {\n");
+        dot.push_str("  ordering=out;\n");
+        dot.push_str("  node [shape=box, fontname=\"Helvetica\"];\n");
+        self.write_dot(&mut dot, &mut Vec::new(), overlay);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(
+        &self,
+        dot: &mut String,
+        path: &mut BehaviorTreePath,
+        overlay: &BehaviorTreeDotOverlay,
+    ) {
+        let label = match self {
+            BehaviorTreeDef::Repeat(_, repeats) => format!("Repeat ({})", repeats),
+            BehaviorTreeDef::GasCost(_, gas_cost) => format!("GasCost ({})", gas_cost),
+            BehaviorTreeDef::GasBudget(_, allowance) => format!("GasBudget ({})", allowance),
+            BehaviorTreeDef::User(node_def) => node_def.label(),
+            BehaviorTreeDef::Wrapper(wrapper_def, _) => format!("Wrapper: {}", wrapper_def.label()),
+            _ => self.kind_name().to_string(),
+        };
+        let id = dot_id(path);
+        let _ = write!(dot, "  {} [label=\"{}\"", id, escape(&label));
+        if let BehaviorTreeDef::User(_) = self {
+            dot.push_str(", shape=ellipse");
+        }
+        if let Some(state) = overlay.outcome(path) {
+            let color = match state {
+                BehaviorTreeState::Complete => "palegreen",
+                BehaviorTreeState::Failed => "lightcoral",
+                BehaviorTreeState::Waiting => "khaki",
+                BehaviorTreeState::WaitingForGas => "lightblue",
+            };
+            let _ = write!(dot, ", style=filled, fillcolor={}", color);
+        }
+        dot.push_str("];\n");
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            let _ = writeln!(dot, "  {} -> {};", id, dot_id(path));
+            child.write_dot(dot, path, overlay);
+            path.pop();
+        }
+    }
+}
+
+// The root is n, its second child n_1, that child's first child n_1_0, and so on.
+fn dot_id(path: &[usize]) -> String {
+    let mut id = "n".to_string();
+    for index in path {
+        let _ = write!(id, "_{}", index);
+    }
+    id
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
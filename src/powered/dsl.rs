@@ -0,0 +1,356 @@
+// Tree definitions as indented text, for example:
+//
+//     sequence:
+//       Say("hello")
+//       repeat 3:
+//         gas_cost 2:
+//           Step(1)
+//       wrapper Retry(2):
+//         Step(2)
+//
+// Built-in nodes are a keyword, an argument where the node needs one, and a ':', with
+// their children indented beneath them. Every other line is a user leaf, written as RON.
+// Blank lines and lines starting with '#' are ignored.
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{BehaviorTreeDef, UserNodeDefinition, UserWrapperDefinition};
+
+// Where in the text an error is, as a 1-based line and column and a length in characters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BehaviorTreeDslError {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+    pub message: String,
+}
+
+impl fmt::Display for BehaviorTreeDslError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for BehaviorTreeDslError {}
+
+const KEYWORDS: [&str; 12] = [
+    "sequence",
+    "selector",
+    "executor",
+    "repeat",
+    "repeat_until_success",
+    "repeat_until_fail",
+    "succeeder",
+    "failer",
+    "inverter",
+    "wrapper",
+    "gas_cost",
+    "gas_budget",
+];
+
+// A parsed line and the lines nested under it, before it is turned into a definition.
+pub(crate) struct DslNode<'a> {
+    line: usize,
+    column: usize,
+    // None for user leaves.
+    keyword: Option<&'a str>,
+    // The text after the keyword, or the whole leaf.
+    argument: &'a str,
+    argument_column: usize,
+    pub(crate) children: Vec<DslNode<'a>>,
+}
+
+impl<'a> DslNode<'a> {
+    pub(crate) fn keyword(&self) -> Option<&'a str> {
+        self.keyword
+    }
+
+    pub(crate) fn error(&self, message: String) -> BehaviorTreeDslError {
+        BehaviorTreeDslError {
+            line: self.line,
+            column: self.column,
+            length: self.keyword.unwrap_or(self.argument).chars().count(),
+            message,
+        }
+    }
+
+    fn argument_error(&self, message: String) -> BehaviorTreeDslError {
+        BehaviorTreeDslError {
+            line: self.line,
+            column: self.argument_column,
+            length: self.argument.chars().count().max(1),
+            message,
+        }
+    }
+
+    pub(crate) fn expect_no_argument(&self) -> Result<(), BehaviorTreeDslError> {
+        if self.argument.is_empty() {
+            Ok(())
+        } else {
+            Err(self.argument_error(format!(
+                "{} takes no argument",
+                self.keyword.unwrap_or_default()
+            )))
+        }
+    }
+
+    pub(crate) fn number<N: std::str::FromStr>(&self) -> Result<N, BehaviorTreeDslError> {
+        self.argument.parse().map_err(|_| {
+            self.argument_error(format!(
+                "{} expects a whole number, found '{}'",
+                self.keyword.unwrap_or_default(),
+                self.argument
+            ))
+        })
+    }
+
+    // Parses the leaf, or a wrapper's argument, as RON.
+    pub(crate) fn value<V: DeserializeOwned>(&self) -> Result<V, BehaviorTreeDslError> {
+        ron::from_str(self.argument).map_err(|error| BehaviorTreeDslError {
+            line: self.line,
+            column: self.argument_column + error.position.col.saturating_sub(1),
+            length: 1,
+            message: error.code.to_string(),
+        })
+    }
+
+    pub(crate) fn only_child(&self) -> Result<&DslNode<'a>, BehaviorTreeDslError> {
+        match self.children.as_slice() {
+            [child] => Ok(child),
+            children => Err(self.error(format!(
+                "{} expects exactly one child, found {}",
+                self.keyword.unwrap_or_default(),
+                children.len()
+            ))),
+        }
+    }
+}
+
+pub(crate) fn parse_dsl(text: &str) -> Result<DslNode<'_>, BehaviorTreeDslError> {
+    let mut lines = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let content = line.trim_start_matches(' ');
+        let indent = line.len() - content.len();
+        if content.trim().is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if content.starts_with('\t') {
+            return Err(BehaviorTreeDslError {
+                line: line_index + 1,
+                column: indent + 1,
+                length: 1,
+                message: "indent with spaces, not tabs".to_string(),
+            });
+        }
+        lines.push((line_index + 1, indent, content.trim_end()));
+    }
+    let Some(&(_, root_indent, _)) = lines.first() else {
+        return Err(BehaviorTreeDslError {
+            line: 1,
+            column: 1,
+            length: 0,
+            message: "expected a tree, found no nodes".to_string(),
+        });
+    };
+    let mut position = 0;
+    let mut roots = parse_block(&lines, &mut position, root_indent)?;
+    if let Some(&(line, indent, content)) = lines.get(position) {
+        return Err(unexpected_indent(line, indent, content));
+    }
+    if roots.len() > 1 {
+        return Err(roots[1].error("expected a single root node".to_string()));
+    }
+    Ok(roots.remove(0))
+}
+
+fn parse_block<'a>(
+    lines: &[(usize, usize, &'a str)],
+    position: &mut usize,
+    indent: usize,
+) -> Result<Vec<DslNode<'a>>, BehaviorTreeDslError> {
+    let mut nodes = Vec::new();
+    while let Some(&(line, line_indent, content)) = lines.get(*position) {
+        if line_indent < indent {
+            break;
+        } else if line_indent > indent {
+            return Err(unexpected_indent(line, line_indent, content));
+        }
+        *position += 1;
+        let mut node = parse_line(line, indent, content)?;
+        if let Some(&(child_line, child_indent, child_content)) = lines.get(*position) {
+            if child_indent > indent {
+                if node.keyword.is_none() {
+                    return Err(unexpected_indent(child_line, child_indent, child_content));
+                }
+                node.children = parse_block(lines, position, child_indent)?;
+            }
+        }
+        nodes.push(node);
+    }
+    Ok(nodes)
+}
+
+fn parse_line(
+    line: usize,
+    indent: usize,
+    content: &str,
+) -> Result<DslNode<'_>, BehaviorTreeDslError> {
+    let column = indent + 1;
+    let word = content
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .next()
+        .unwrap_or_default();
+    if !KEYWORDS.contains(&word) {
+        return Ok(DslNode {
+            line,
+            column,
+            keyword: None,
+            argument: content,
+            argument_column: column,
+            children: Vec::new(),
+        });
+    }
+    let Some(rest) = content.strip_suffix(':') else {
+        return Err(BehaviorTreeDslError {
+            line,
+            column: column + content.chars().count(),
+            length: 1,
+            message: format!("expected ':' after {}", word),
+        });
+    };
+    let argument = rest[word.len()..].trim_start();
+    Ok(DslNode {
+        line,
+        column,
+        keyword: Some(word),
+        argument: argument.trim_end(),
+        argument_column: column + rest.chars().count() - argument.chars().count(),
+        children: Vec::new(),
+    })
+}
+
+fn unexpected_indent(line: usize, indent: usize, content: &str) -> BehaviorTreeDslError {
+    BehaviorTreeDslError {
+        line,
+        column: indent + 1,
+        length: content.chars().count(),
+        message: "unexpected indentation".to_string(),
+    }
+}
+
+// Writes a node line at the given depth, with its value or argument rendered as RON.
+pub(crate) fn write_dsl_line(
+    text: &mut String,
+    depth: usize,
+    keyword: Option<&str>,
+    argument: &str,
+) {
+    for _ in 0..depth {
+        text.push_str("  ");
+    }
+    match keyword {
+        Some(keyword) if argument.is_empty() => text.push_str(keyword),
+        Some(keyword) => {
+            text.push_str(keyword);
+            text.push(' ');
+            text.push_str(argument);
+        }
+        None => text.push_str(argument),
+    }
+    if keyword.is_some() {
+        text.push(':');
+    }
+    text.push('\n');
+}
+
+pub(crate) fn to_ron<V: Serialize>(value: &V, text: &str) -> Result<String, BehaviorTreeDslError> {
+    ron::to_string(value).map_err(|error| BehaviorTreeDslError {
+        line: text.lines().count() + 1,
+        column: 1,
+        length: 0,
+        message: error.to_string(),
+    })
+}
+
+impl<U, W> BehaviorTreeDef<U, W>
+where
+    U: UserNodeDefinition + DeserializeOwned,
+    W: UserWrapperDefinition<U> + DeserializeOwned,
+{
+    pub fn from_dsl(text: &str) -> Result<Self, BehaviorTreeDslError> {
+        Self::from_dsl_node(&parse_dsl(text)?)
+    }
+
+    fn from_dsl_node(node: &DslNode) -> Result<Self, BehaviorTreeDslError> {
+        let Some(keyword) = node.keyword() else {
+            return Ok(BehaviorTreeDef::User(node.value()?));
+        };
+        let only_child = || -> Result<Box<Self>, BehaviorTreeDslError> {
+            Ok(Box::new(Self::from_dsl_node(node.only_child()?)?))
+        };
+        let all_children = || -> Result<Vec<Self>, BehaviorTreeDslError> {
+            node.children.iter().map(Self::from_dsl_node).collect()
+        };
+        if matches!(keyword, "executor") {
+            return Err(node.error(format!("{} is only available in unpowered trees", keyword)));
+        }
+        if !matches!(keyword, "repeat" | "wrapper" | "gas_cost" | "gas_budget") {
+            node.expect_no_argument()?;
+        }
+        Ok(match keyword {
+            "sequence" => BehaviorTreeDef::Sequence(all_children()?),
+            "selector" => BehaviorTreeDef::Selector(all_children()?),
+            "repeat" => BehaviorTreeDef::Repeat(only_child()?, node.number()?),
+            "repeat_until_success" => BehaviorTreeDef::RepeatUntilSuccess(only_child()?),
+            "repeat_until_fail" => BehaviorTreeDef::RepeatUntilFail(only_child()?),
+            "succeeder" => BehaviorTreeDef::Succeeder(only_child()?),
+            "failer" => BehaviorTreeDef::Failer(only_child()?),
+            "inverter" => BehaviorTreeDef::Inverter(only_child()?),
+            "wrapper" => BehaviorTreeDef::Wrapper(node.value()?, all_children()?),
+            "gas_cost" => BehaviorTreeDef::GasCost(only_child()?, node.number()?),
+            "gas_budget" => BehaviorTreeDef::GasBudget(only_child()?, node.number()?),
+            _ => unreachable!("the parser only accepts known keywords"),
+        })
+    }
+}
+
+impl<U, W> BehaviorTreeDef<U, W>
+where
+    U: UserNodeDefinition + Serialize,
+    W: UserWrapperDefinition<U> + Serialize,
+{
+    // Writes the definition in the text format, which from_dsl reads back unchanged.
+    pub fn to_dsl(&self) -> Result<String, BehaviorTreeDslError> {
+        let mut text = String::new();
+        self.write_dsl(&mut text, 0)?;
+        Ok(text)
+    }
+
+    fn write_dsl(&self, text: &mut String, depth: usize) -> Result<(), BehaviorTreeDslError> {
+        let (keyword, argument) = match self {
+            BehaviorTreeDef::Sequence(_) => ("sequence", String::new()),
+            BehaviorTreeDef::Selector(_) => ("selector", String::new()),
+            BehaviorTreeDef::Repeat(_, repeats) => ("repeat", repeats.to_string()),
+            BehaviorTreeDef::RepeatUntilSuccess(_) => ("repeat_until_success", String::new()),
+            BehaviorTreeDef::RepeatUntilFail(_) => ("repeat_until_fail", String::new()),
+            BehaviorTreeDef::Succeeder(_) => ("succeeder", String::new()),
+            BehaviorTreeDef::Failer(_) => ("failer", String::new()),
+            BehaviorTreeDef::Inverter(_) => ("inverter", String::new()),
+            BehaviorTreeDef::User(node_def) => {
+                let value = to_ron(node_def, text)?;
+                write_dsl_line(text, depth, None, &value);
+                return Ok(());
+            }
+            BehaviorTreeDef::Wrapper(wrapper_def, _) => ("wrapper", to_ron(wrapper_def, text)?),
+            BehaviorTreeDef::GasCost(_, gas_cost) => ("gas_cost", gas_cost.to_string()),
+            BehaviorTreeDef::GasBudget(_, allowance) => ("gas_budget", allowance.to_string()),
+        };
+        write_dsl_line(text, depth, Some(keyword), &argument);
+        for child in self.children() {
+            child.write_dsl(text, depth + 1)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+// A budget that nodes spend as they work. When it runs out, nodes return
+// BehaviorTreeState::WaitingForGas and pick up where they left off on the next resume.
+//
+// Built-in nodes only use this trait, so any budget representation works: abstract op counts,
+// milliseconds, or a composite of several resources. User leaves are free to spend their
+// concrete gas type however they like.
+pub trait Gas {
+    // Whether there is any budget left. Unlimited budgets always have gas.
+    fn has_gas(&self) -> bool;
+
+    // Spends cost units if the budget can afford them, returning whether it did.
+    // A budget that can't afford the cost is left unchanged.
+    fn try_consume(&mut self, cost: u32) -> bool;
+
+    // The remaining budget as a single quantity, or None if it is unlimited.
+    // Used for reporting, such as by the profiler.
+    fn remaining(&self) -> Option<f64>;
+
+    // Takes up to allowance units out of this budget as a separate budget,
+    // e.g. to cap what a subtree can spend. Unlimited budgets carve exactly the allowance.
+    fn carve(&mut self, allowance: u32) -> Self
+    where
+        Self: Sized;
+
+    // Returns whatever is left of a carved budget.
+    fn refund(&mut self, unspent: Self)
+    where
+        Self: Sized;
+
+    // Whether built-in nodes that charge nothing may still go on. Counted budgets are only
+    // spent by costs, so free nodes never need to ask them; budgets that run out by
+    // themselves, like a Deadline, override this to check that they haven't.
+    fn has_gas_for_free_nodes(&self) -> bool {
+        true
+    }
+
+    // Called on a copy of the budget a runner resets to at the start of each tick. Counted
+    // budgets are full already; budgets measured from when they were made, like a Deadline
+    // made with after, override this to start over from now.
+    fn restart(&mut self) {}
+}
+
+// None is an unlimited budget.
+impl Gas for Option<i32> {
+    fn has_gas(&self) -> bool {
+        match self {
+            Some(gas) => *gas > 0,
+            None => true,
+        }
+    }
+
+    fn try_consume(&mut self, cost: u32) -> bool {
+        match self {
+            Some(gas) => match gas.checked_sub_unsigned(cost) {
+                Some(left) if left >= 0 => {
+                    *gas = left;
+                    true
+                }
+                _ => false,
+            },
+            None => true,
+        }
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        self.map(|gas| gas as f64)
+    }
+
+    fn carve(&mut self, allowance: u32) -> Self {
+        match self {
+            Some(gas) => Some(gas.carve(allowance)),
+            None => Some(allowance.min(i32::MAX as u32) as i32),
+        }
+    }
+
+    fn refund(&mut self, unspent: Self) {
+        if let (Some(gas), Some(unspent)) = (self, unspent) {
+            gas.refund(unspent);
+        }
+    }
+}
+
+impl Gas for i32 {
+    fn has_gas(&self) -> bool {
+        *self > 0
+    }
+
+    fn try_consume(&mut self, cost: u32) -> bool {
+        match self.checked_sub_unsigned(cost) {
+            Some(left) if left >= 0 => {
+                *self = left;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
+
+    fn carve(&mut self, allowance: u32) -> Self {
+        let carved = (*self).clamp(0, allowance.min(i32::MAX as u32) as i32);
+        *self -= carved;
+        carved
+    }
+
+    // Leaves may overspend a carved budget, in which case the overspend is taken from this one.
+    fn refund(&mut self, unspent: Self) {
+        *self += unspent;
+    }
+}
+
+impl Gas for u32 {
+    fn has_gas(&self) -> bool {
+        *self > 0
+    }
+
+    fn try_consume(&mut self, cost: u32) -> bool {
+        match self.checked_sub(cost) {
+            Some(left) => {
+                *self = left;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
+
+    fn carve(&mut self, allowance: u32) -> Self {
+        let carved = (*self).min(allowance);
+        *self -= carved;
+        carved
+    }
+
+    fn refund(&mut self, unspent: Self) {
+        *self += unspent;
+    }
+}
+
+impl Gas for u64 {
+    fn has_gas(&self) -> bool {
+        *self > 0
+    }
+
+    fn try_consume(&mut self, cost: u32) -> bool {
+        match self.checked_sub(cost as u64) {
+            Some(left) => {
+                *self = left;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
+
+    fn carve(&mut self, allowance: u32) -> Self {
+        let carved = (*self).min(allowance as u64);
+        *self -= carved;
+        carved
+    }
+
+    fn refund(&mut self, unspent: Self) {
+        *self += unspent;
+    }
+}
+
+// A continuous budget, such as milliseconds of CPU time.
+impl Gas for f64 {
+    fn has_gas(&self) -> bool {
+        *self > 0.0
+    }
+
+    fn try_consume(&mut self, cost: u32) -> bool {
+        if *self >= cost as f64 {
+            *self -= cost as f64;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        Some(*self)
+    }
+
+    fn carve(&mut self, allowance: u32) -> Self {
+        let carved = self.clamp(0.0, allowance as f64);
+        *self -= carved;
+        carved
+    }
+
+    fn refund(&mut self, unspent: Self) {
+        *self += unspent;
+    }
+}
+
+// Two resources budgeted together, e.g. CPU ops and pathfinding queries. Both must have gas
+// left to continue. Costs charged by built-in nodes come out of the first resource; leaves
+// spend the second directly.
+impl<A: Gas, B: Gas> Gas for (A, B) {
+    fn has_gas(&self) -> bool {
+        self.0.has_gas() && self.1.has_gas()
+    }
+
+    fn try_consume(&mut self, cost: u32) -> bool {
+        self.1.has_gas() && self.0.try_consume(cost)
+    }
+
+    fn remaining(&self) -> Option<f64> {
+        self.0.remaining()
+    }
+
+    // Only the first resource is limited by the allowance; the second is lent out whole.
+    fn carve(&mut self, allowance: u32) -> Self {
+        (self.0.carve(allowance), self.1.carve(u32::MAX))
+    }
+
+    fn refund(&mut self, unspent: Self) {
+        self.0.refund(unspent.0);
+        self.1.refund(unspent.1);
+    }
+
+    fn has_gas_for_free_nodes(&self) -> bool {
+        self.0.has_gas_for_free_nodes() && self.1.has_gas_for_free_nodes()
+    }
+
+    fn restart(&mut self) {
+        self.0.restart();
+        self.1.restart();
+    }
+}
+
+// Charges a built-in node's gas cost. Free nodes only check budgets that run out by
+// themselves, so free built-in nodes behave as if counted gas didn't exist for them.
+pub(crate) fn charge_gas<G: Gas>(gas: &mut G, cost: u32) -> bool {
+    if cost == 0 {
+        gas.has_gas_for_free_nodes()
+    } else {
+        gas.try_consume(cost)
+    }
+}
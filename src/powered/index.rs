@@ -0,0 +1,126 @@
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{BehaviorTree, BehaviorTreeMarker};
+
+// The child indices leading from the root to a node. The root is the empty path.
+// Paths are shared between a BehaviorTreeDef and the tree created from it.
+pub type BehaviorTreePath = Vec<usize>;
+
+// Maps the names of a live tree's nodes to their paths, so audits can be related back to
+// the definition the tree was created from.
+//
+// Only nodes given out by children are indexed. A wrapper that hides the nodes it wraps is
+// opaque: it stands in for its whole subtree, and events of the hidden nodes are traced to it.
+// An UnpoweredNode is opaque in the same way. The unpowered nodes it runs aren't indexed,
+// and unpowered leaves without a name of their own all audit under the empty name.
+//
+// Node names are only given out when a tree is created, so the index can't be rebuilt from
+// the definition later. Save it along with any audit meant to be replayed elsewhere.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+pub struct BehaviorTreeIndex {
+    paths: HashMap<String, Vec<BehaviorTreePath>>,
+    // The paths of nodes that give out no children: leaves, and opaque wrappers.
+    childless: HashSet<BehaviorTreePath>,
+}
+
+impl BehaviorTreeIndex {
+    pub fn new<G, T: BehaviorTree<G> + ?Sized>(tree: &T) -> Self {
+        let mut index = BehaviorTreeIndex::default();
+        index.add(tree, &mut Vec::new());
+        index
+    }
+
+    fn add<G, T: BehaviorTree<G> + ?Sized>(&mut self, node: &T, path: &mut BehaviorTreePath) {
+        self.paths
+            .entry(node.get_name().clone())
+            .or_default()
+            .push(path.clone());
+        if node.children().is_empty() {
+            self.childless.insert(path.clone());
+        }
+        for (child_index, child) in node.children().iter().enumerate() {
+            path.push(child_index);
+            self.add(child.as_ref(), path);
+            path.pop();
+        }
+    }
+
+    // Every path of a node with the given name. Built-in nodes have unique names,
+    // but user nodes may share theirs.
+    pub fn paths_of(&self, node_name: &str) -> &[BehaviorTreePath] {
+        self.paths
+            .get(node_name)
+            .map(|paths| paths.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub(crate) fn childless(&self) -> &HashSet<BehaviorTreePath> {
+        &self.childless
+    }
+
+    // Finds the path of a named node, preferring a child of the given parent when the
+    // name is ambiguous.
+    pub fn locate(&self, node_name: &str, parent: Option<&[usize]>) -> Option<&BehaviorTreePath> {
+        let paths = self.paths_of(node_name);
+        parent
+            .and_then(|parent| {
+                paths
+                    .iter()
+                    .find(|path| path.len() == parent.len() + 1 && path.starts_with(parent))
+            })
+            .or_else(|| paths.first())
+    }
+
+    // The path of the node each event belongs to, following the nesting of Enter and Exit
+    // events. Markers belong to the innermost entered node unless they name another node.
+    // Events of nodes missing from the index are traced to None, but anything they enter or
+    // mark is placed as if it were inside their indexed parent.
+    pub fn trace<'a>(
+        &self,
+        events: impl IntoIterator<Item = &'a BehaviorTreeMarker>,
+    ) -> Vec<Option<BehaviorTreePath>> {
+        // The entered nodes: their names, their paths and the path their contents are placed in.
+        let mut stack: Vec<(
+            &'a String,
+            Option<BehaviorTreePath>,
+            Option<BehaviorTreePath>,
+        )> = Vec::new();
+        let mut traced = Vec::new();
+        for event in events {
+            let parent = stack.last().and_then(|(_, _, scope)| scope.clone());
+            match event {
+                BehaviorTreeMarker::Enter(node_name) => {
+                    let path = self.locate(node_name, parent.as_deref()).cloned();
+                    let scope = path.clone().or(parent);
+                    stack.push((node_name, path.clone(), scope));
+                    traced.push(path);
+                }
+                BehaviorTreeMarker::Marker(node_name)
+                | BehaviorTreeMarker::MarkerWithPayload(node_name, _) => {
+                    let path = if self.paths_of(node_name).is_empty() {
+                        parent
+                    } else {
+                        self.locate(node_name, parent.as_deref()).cloned()
+                    };
+                    traced.push(path);
+                }
+                BehaviorTreeMarker::Exit(node_name, _) => {
+                    if stack.last().map(|(name, _, _)| *name == node_name) == Some(true) {
+                        traced.push(stack.pop().unwrap().1);
+                    } else {
+                        traced.push(self.locate(node_name, None).cloned());
+                    }
+                }
+                BehaviorTreeMarker::Tick(_) => {
+                    stack.clear();
+                    traced.push(None);
+                }
+            }
+        }
+        traced
+    }
+}
@@ -1,4 +1,46 @@
+mod adapters;
+mod batch;
+mod builder;
+mod coverage;
+mod deadline;
+mod dot;
+#[cfg(feature = "dsl")]
+mod dsl;
+mod gas;
+mod index;
 mod nodes;
+mod outcomes;
+mod payload;
+mod profile;
+mod reload;
+mod replay;
+mod runner;
+mod scheduler;
+#[cfg(feature = "schema")]
+mod schema;
+mod simplify;
 mod tree_def;
+mod validate;
+mod visit;
+pub use adapters::*;
+pub use batch::*;
+pub use coverage::*;
+pub use deadline::*;
+pub use dot::*;
+#[cfg(feature = "dsl")]
+pub use dsl::*;
+pub use gas::*;
+pub use index::*;
 pub use nodes::*;
+pub use outcomes::*;
+pub use payload::*;
+pub use profile::*;
+pub use reload::*;
+pub use replay::*;
+pub use runner::*;
+pub use scheduler::*;
+#[cfg(feature = "schema")]
+pub(crate) use schema::{json_schema, SchemaVariant};
+pub use simplify::*;
 pub use tree_def::*;
+pub use validate::*;
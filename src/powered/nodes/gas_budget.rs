@@ -0,0 +1,57 @@
+use super::super::*;
+
+// Runs its child on an allowance carved out of the outer gas each time it is resumed,
+// so the subtree can't spend more than the allowance per tick. Unspent gas is refunded.
+pub struct GasBudget<M, C, G = Option<i32>> {
+    name: String,
+    node: BoxedBehaviorTree<G, M, C>,
+    allowance: u32,
+}
+
+impl<M, C, G> GasBudget<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>, allowance: u32) -> Self {
+        GasBudget {
+            name: get_bt_id(),
+            node,
+            allowance,
+        }
+    }
+}
+
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for GasBudget<M, C, G> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        gas: &mut G,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        audit.enter(self.get_name());
+        let mut allowance = gas.carve(self.allowance);
+        let result = self
+            .node
+            .resume_with(model, controller, &mut allowance, audit);
+        gas.refund(allowance);
+        // WaitingForGas may come from the allowance alone; the outer budget can still have gas.
+        audit.exit(self.get_name(), result);
+        result
+    }
+
+    fn reset(self: &mut Self, model: &Self::Model) {
+        self.node.reset(model);
+    }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_ref(&self.node)
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_mut(&mut self.node)
+    }
+}
@@ -0,0 +1,57 @@
+use super::super::*;
+
+// Charges a fixed amount of gas each time its child is entered or resumed,
+// waiting for gas if the budget can't afford it. Being a node of its own, it adds a level
+// to the tree; see BehaviorTreeDef::GasCost.
+pub struct GasCost<M, C, G = Option<i32>> {
+    name: String,
+    node: BoxedBehaviorTree<G, M, C>,
+    gas_cost: u32,
+}
+
+impl<M, C, G> GasCost<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>, gas_cost: u32) -> Self {
+        GasCost {
+            name: get_bt_id(),
+            node,
+            gas_cost,
+        }
+    }
+}
+
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for GasCost<M, C, G> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        gas: &mut G,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        audit.enter(self.get_name());
+        if !charge_gas(gas, self.gas_cost) {
+            audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+            return BehaviorTreeState::WaitingForGas;
+        }
+        let result = self.node.resume_with(model, controller, gas, audit);
+        audit.exit(self.get_name(), result);
+        result
+    }
+
+    fn reset(self: &mut Self, model: &Self::Model) {
+        self.node.reset(model);
+    }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_ref(&self.node)
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_mut(&mut self.node)
+    }
+}
@@ -1,30 +1,41 @@
 use super::super::*;
 
-pub struct Inverter<M, C> {
+pub struct Inverter<M, C, G = Option<i32>> {
     name: String,
-    node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>,
+    node: BoxedBehaviorTree<G, M, C>,
+    gas_cost: u32,
 }
 
-impl<M, C> Inverter<M, C> {
-    pub fn new(node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>) -> Self {
+impl<M, C, G> Inverter<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>) -> Self {
         Inverter {
             name: get_bt_id(),
             node,
+            gas_cost: 0,
         }
     }
+
+    pub fn with_gas_cost(mut self, gas_cost: u32) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
 }
 
-impl<M: 'static, C: 'static> BehaviorTree for Inverter<M, C> {
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for Inverter<M, C, G> {
     type Model = M;
     type Controller = C;
     fn resume_with(
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         mut audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState {
         audit.enter(self.get_name());
+        if !charge_gas(gas, self.gas_cost) {
+            audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+            return BehaviorTreeState::WaitingForGas;
+        }
         match self.node.resume_with(model, controller, gas, audit) {
             BehaviorTreeState::Complete => {
                 audit.exit(self.get_name(), BehaviorTreeState::Failed);
@@ -49,4 +60,12 @@ impl<M: 'static, C: 'static> BehaviorTree for Inverter<M, C> {
     fn get_name(self: &Self) -> &String {
         &self.name
     }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_ref(&self.node)
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_mut(&mut self.node)
+    }
 }
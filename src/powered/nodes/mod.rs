@@ -1,4 +1,6 @@
 mod failer;
+mod gas_budget;
+mod gas_cost;
 mod inverter;
 mod repeat;
 mod selector;
@@ -6,32 +8,221 @@ mod sequence;
 mod succeeder;
 
 pub use failer::*;
+pub use gas_budget::*;
+pub use gas_cost::*;
 pub use inverter::*;
 pub use repeat::*;
 pub use selector::*;
 pub use sequence::*;
 pub use succeeder::*;
 
+use super::{charge_gas, BehaviorTreePayload, Gas};
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::Ordering;
-
-#[cfg(feature = "tracing")]
-use lazy_static::lazy_static;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 pub enum BehaviorTreeMarker {
     Enter(String),
     Marker(String),
+    // A mark with structured details, as recorded by mark_with.
+    MarkerWithPayload(String, BehaviorTreePayload),
     Exit(String, BehaviorTreeState),
+    // The start of a new tick, as recorded by BehaviorTreeAudit::start_tick.
+    Tick(u64),
+}
+
+// How much history a BehaviorTreeAudit keeps before discarding the oldest events.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum BehaviorTreeAuditLimit {
+    #[default]
+    Unbounded,
+    // Keep at most this many events.
+    Events(usize),
+    // Keep the events of at most this many ticks, including the current one.
+    Ticks(usize),
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone)]
 pub struct BehaviorTreeAudit {
-    events: Vec<BehaviorTreeMarker>,
+    events: VecDeque<BehaviorTreeMarker>,
     place: Vec<String>,
+    // Audits saved before these fields existed load as unbounded, at tick 0.
+    #[cfg_attr(feature = "serde", serde(default))]
+    limit: BehaviorTreeAuditLimit,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tick: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    ticks_retained: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    dropped: usize,
+}
+
+impl Default for BehaviorTreeAudit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BehaviorTreeAudit {
+    pub fn new() -> Self {
+        Self::bounded(BehaviorTreeAuditLimit::Unbounded)
+    }
+
+    pub fn bounded(limit: BehaviorTreeAuditLimit) -> Self {
+        BehaviorTreeAudit {
+            events: VecDeque::new(),
+            place: Vec::new(),
+            limit,
+            tick: 0,
+            ticks_retained: 0,
+            dropped: 0,
+        }
+    }
+
+    pub fn limit(&self) -> BehaviorTreeAuditLimit {
+        self.limit
+    }
+
+    // Records a tick boundary. Call this before resuming the root each tick.
+    pub fn start_tick(&mut self) {
+        self.tick += 1;
+        self.ticks_retained += 1;
+        self.push(BehaviorTreeMarker::Tick(self.tick));
+    }
+
+    // The number of the most recently started tick, or 0 if start_tick was never called.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &BehaviorTreeMarker> + '_ {
+        self.events.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    // The number of events discarded so far to stay within the limit.
+    pub fn dropped_events(&self) -> usize {
+        self.dropped
+    }
+
+    // The retained events, grouped by the tick they were recorded in.
+    // Events recorded before the first retained tick boundary are grouped under None.
+    pub fn ticks(&self) -> Vec<(Option<u64>, Vec<&BehaviorTreeMarker>)> {
+        let mut ticks: Vec<(Option<u64>, Vec<&BehaviorTreeMarker>)> = Vec::new();
+        for event in self.events.iter() {
+            match event {
+                BehaviorTreeMarker::Tick(tick) => ticks.push((Some(*tick), Vec::new())),
+                _ => {
+                    if ticks.is_empty() {
+                        ticks.push((None, Vec::new()));
+                    }
+                    ticks.last_mut().unwrap().1.push(event);
+                }
+            }
+        }
+        ticks
+    }
+
+    // Takes the retained events, leaving the audit empty but keeping its limit and tick count.
+    pub fn drain(&mut self) -> Vec<BehaviorTreeMarker> {
+        self.ticks_retained = 0;
+        self.place.clear();
+        self.events.drain(..).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.drain();
+    }
+
+    fn push(&mut self, marker: BehaviorTreeMarker) {
+        self.events.push_back(marker);
+        match self.limit {
+            BehaviorTreeAuditLimit::Unbounded => {}
+            BehaviorTreeAuditLimit::Events(max_events) => {
+                while self.events.len() > max_events {
+                    self.pop_oldest();
+                }
+            }
+            BehaviorTreeAuditLimit::Ticks(max_ticks) => {
+                while self.ticks_retained > max_ticks.max(1) {
+                    // Discard everything up to and including the oldest tick boundary...
+                    loop {
+                        match self.pop_oldest() {
+                            Some(BehaviorTreeMarker::Tick(_)) | None => break,
+                            Some(_) => {}
+                        }
+                    }
+                    // ...and the rest of that tick.
+                    while !matches!(
+                        self.events.front(),
+                        Some(BehaviorTreeMarker::Tick(_)) | None
+                    ) {
+                        self.pop_oldest();
+                    }
+                }
+            }
+        }
+    }
+
+    fn pop_oldest(&mut self) -> Option<BehaviorTreeMarker> {
+        let oldest = self.events.pop_front();
+        if let Some(marker) = &oldest {
+            self.dropped += 1;
+            if let BehaviorTreeMarker::Tick(_) = marker {
+                self.ticks_retained -= 1;
+            }
+        }
+        oldest
+    }
+}
+
+impl fmt::Display for BehaviorTreeMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BehaviorTreeMarker::Enter(node_name) => write!(f, "enter {}", node_name),
+            BehaviorTreeMarker::Marker(node_name) => write!(f, "mark {}", node_name),
+            BehaviorTreeMarker::MarkerWithPayload(node_name, payload) => {
+                write!(f, "mark {}: {}", node_name, payload)
+            }
+            BehaviorTreeMarker::Exit(node_name, state) => {
+                write!(f, "exit {} {:?}", node_name, state)
+            }
+            BehaviorTreeMarker::Tick(tick) => write!(f, "tick {}", tick),
+        }
+    }
+}
+
+// One event per line, indented by nesting depth.
+impl fmt::Display for BehaviorTreeAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut depth: usize = 0;
+        for event in self.events.iter() {
+            match event {
+                BehaviorTreeMarker::Exit(_, _) => depth = depth.saturating_sub(1),
+                BehaviorTreeMarker::Tick(_) => depth = 0,
+                _ => {}
+            }
+            writeln!(f, "{:indent$}{}", "", event, indent = depth * 2)?;
+            if let BehaviorTreeMarker::Enter(_) = event {
+                depth += 1;
+            }
+        }
+        Ok(())
+    }
 }
 
 pub trait BehaviorTreeAuditTrait {
@@ -39,24 +230,33 @@ pub trait BehaviorTreeAuditTrait {
 
     fn mark(&mut self, node_name: &String);
 
+    // Marks with structured details. Audits that cannot store payloads record a plain mark.
+    fn mark_with(&mut self, node_name: &String, _payload: BehaviorTreePayload) {
+        self.mark(node_name);
+    }
+
     fn exit(&mut self, node_name: &String, state: BehaviorTreeState);
 }
 
 impl BehaviorTreeAuditTrait for BehaviorTreeAudit {
     fn enter(&mut self, node_name: &String) {
-        self.events
-            .push(BehaviorTreeMarker::Enter(node_name.clone()));
+        self.push(BehaviorTreeMarker::Enter(node_name.clone()));
         self.place.push(node_name.clone());
     }
 
     fn mark(&mut self, node_name: &String) {
-        self.events
-            .push(BehaviorTreeMarker::Marker(node_name.clone()));
+        self.push(BehaviorTreeMarker::Marker(node_name.clone()));
+    }
+
+    fn mark_with(&mut self, node_name: &String, payload: BehaviorTreePayload) {
+        self.push(BehaviorTreeMarker::MarkerWithPayload(
+            node_name.clone(),
+            payload,
+        ));
     }
 
     fn exit(&mut self, node_name: &String, state: BehaviorTreeState) {
-        self.events
-            .push(BehaviorTreeMarker::Exit(node_name.clone(), state));
+        self.push(BehaviorTreeMarker::Exit(node_name.clone(), state));
         if let Some(current_node_name) = self.place.last() {
             if current_node_name.eq(node_name) {
                 self.place.pop();
@@ -81,6 +281,12 @@ where
         }
     }
 
+    fn mark_with(&mut self, node_name: &String, payload: BehaviorTreePayload) {
+        if let Some(audit) = self {
+            audit.mark_with(node_name, payload);
+        }
+    }
+
     fn exit(&mut self, node_name: &String, state: BehaviorTreeState) {
         if let Some(audit) = self {
             audit.exit(node_name, state);
@@ -100,17 +306,16 @@ pub enum BehaviorTreeState {
     Complete,
 }
 
-#[cfg(feature = "tracing")]
-lazy_static! {
-    pub static ref DEFAULT_IDS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
-}
+// Every build numbers nodes from this one counter, so nodes are told apart by audits,
+// profiles and indexes with or without tracing.
+pub static DEFAULT_IDS: AtomicU32 = AtomicU32::new(0);
 
-#[cfg(feature = "tracing")]
 pub fn get_bt_id() -> String {
     format!("<node {}>", DEFAULT_IDS.fetch_add(1, Ordering::SeqCst))
 }
 
-pub trait BehaviorTree {
+// G is the gas budget type; see the Gas trait.
+pub trait BehaviorTree<G = Option<i32>> {
     type Model: 'static;
     type Controller: 'static;
     fn get_name(self: &Self) -> &String;
@@ -119,9 +324,25 @@ pub trait BehaviorTree {
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState;
 
     fn reset(self: &mut Self, model: &Self::Model);
+
+    // The node's direct children, in definition order. Leaves have none, and wrappers should
+    // give out the nodes they wrap; see UserWrapperDefinition.
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        &[]
+    }
+
+    // The same children, for swapping them out when a tree is reloaded. Nodes that
+    // return children from children should return them here too.
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        &mut []
+    }
 }
+
+// A tree or subtree as nodes own them and runners hold them.
+pub type BoxedBehaviorTree<G, M, C> =
+    Box<dyn BehaviorTree<G, Model = M, Controller = C> + Send + Sync>;
@@ -1,38 +1,46 @@
 use super::super::*;
 
-pub struct Repeat<M, C> {
+pub struct Repeat<M, C, G = Option<i32>> {
     name: String,
-    node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>,
+    node: BoxedBehaviorTree<G, M, C>,
     runs: usize,
     runs_left: usize,
+    gas_cost: u32,
 }
 
-impl<M, C> Repeat<M, C> {
-    pub fn new(
-        node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>,
-        runs: usize,
-    ) -> Self {
+impl<M, C, G> Repeat<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>, runs: usize) -> Self {
         Repeat {
             name: get_bt_id(),
             node,
             runs,
             runs_left: runs,
+            gas_cost: 0,
         }
     }
+
+    pub fn with_gas_cost(mut self, gas_cost: u32) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
 }
 
-impl<M: 'static, C: 'static> BehaviorTree for Repeat<M, C> {
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for Repeat<M, C, G> {
     type Model = M;
     type Controller = C;
     fn resume_with(
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         mut audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState {
         audit.enter(self.get_name());
         while self.runs_left > 0 {
+            if !charge_gas(gas, self.gas_cost) {
+                audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+                return BehaviorTreeState::WaitingForGas;
+            }
             let result = self.node.resume_with(model, controller, gas, audit);
             match result {
                 BehaviorTreeState::Failed => {
@@ -62,33 +70,52 @@ impl<M: 'static, C: 'static> BehaviorTree for Repeat<M, C> {
     fn get_name(self: &Self) -> &String {
         &self.name
     }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_ref(&self.node)
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_mut(&mut self.node)
+    }
 }
-pub struct RepeatUntilFail<M, C> {
+pub struct RepeatUntilFail<M, C, G = Option<i32>> {
     name: String,
-    node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>,
+    node: BoxedBehaviorTree<G, M, C>,
+    gas_cost: u32,
 }
 
-impl<M, C> RepeatUntilFail<M, C> {
-    pub fn new(node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>) -> Self {
+impl<M, C, G> RepeatUntilFail<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>) -> Self {
         RepeatUntilFail {
             name: get_bt_id(),
             node,
+            gas_cost: 0,
         }
     }
+
+    pub fn with_gas_cost(mut self, gas_cost: u32) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
 }
 
-impl<M: 'static, C: 'static> BehaviorTree for RepeatUntilFail<M, C> {
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for RepeatUntilFail<M, C, G> {
     type Model = M;
     type Controller = C;
     fn resume_with(
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         mut audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState {
         audit.enter(self.get_name());
         loop {
+            if !charge_gas(gas, self.gas_cost) {
+                audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+                return BehaviorTreeState::WaitingForGas;
+            }
             let result = self.node.resume_with(model, controller, gas, audit);
             match result {
                 BehaviorTreeState::Failed => {
@@ -115,34 +142,53 @@ impl<M: 'static, C: 'static> BehaviorTree for RepeatUntilFail<M, C> {
     fn get_name(self: &Self) -> &String {
         &self.name
     }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_ref(&self.node)
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_mut(&mut self.node)
+    }
 }
 
-pub struct RepeatUntilSuccess<M, C> {
+pub struct RepeatUntilSuccess<M, C, G = Option<i32>> {
     name: String,
-    node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>,
+    node: BoxedBehaviorTree<G, M, C>,
+    gas_cost: u32,
 }
 
-impl<M, C> RepeatUntilSuccess<M, C> {
-    pub fn new(node: Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>) -> Self {
+impl<M, C, G> RepeatUntilSuccess<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>) -> Self {
         RepeatUntilSuccess {
             name: get_bt_id(),
             node,
+            gas_cost: 0,
         }
     }
+
+    pub fn with_gas_cost(mut self, gas_cost: u32) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
 }
 
-impl<M: 'static, C: 'static> BehaviorTree for RepeatUntilSuccess<M, C> {
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for RepeatUntilSuccess<M, C, G> {
     type Model = M;
     type Controller = C;
     fn resume_with(
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         mut audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState {
         audit.enter(self.get_name());
         loop {
+            if !charge_gas(gas, self.gas_cost) {
+                audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+                return BehaviorTreeState::WaitingForGas;
+            }
             let result = self.node.resume_with(model, controller, gas, audit);
             match result {
                 BehaviorTreeState::Complete => {
@@ -169,4 +215,12 @@ impl<M: 'static, C: 'static> BehaviorTree for RepeatUntilSuccess<M, C> {
     fn get_name(self: &Self) -> &String {
         &self.name
     }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_ref(&self.node)
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        std::slice::from_mut(&mut self.node)
+    }
 }
@@ -1,34 +1,46 @@
 use super::super::*;
 
-pub struct Selector<M, C> {
+pub struct Selector<M, C, G = Option<i32>> {
     name: String,
-    nodes: Vec<Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>>,
+    nodes: Vec<BoxedBehaviorTree<G, M, C>>,
     index: Option<usize>,
+    gas_cost: u32,
 }
 
-impl<M, C> Selector<M, C> {
-    pub fn new(nodes: Vec<Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>>) -> Self {
+impl<M, C, G> Selector<M, C, G> {
+    pub fn new(nodes: Vec<BoxedBehaviorTree<G, M, C>>) -> Self {
         Selector {
             name: get_bt_id(),
             nodes,
             index: None,
+            gas_cost: 0,
         }
     }
+
+    pub fn with_gas_cost(mut self, gas_cost: u32) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
 }
 
-impl<M: 'static, C: 'static> BehaviorTree for Selector<M, C> {
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for Selector<M, C, G> {
     type Model = M;
     type Controller = C;
     fn resume_with(
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         mut audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState {
         audit.enter(self.get_name());
         let mut running_index = self.index.unwrap_or(0);
         loop {
+            if running_index < self.nodes.len() && !charge_gas(gas, self.gas_cost) {
+                self.index = Some(running_index);
+                audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+                return BehaviorTreeState::WaitingForGas;
+            }
             if let Some(node) = self.nodes.get_mut(running_index) {
                 let result = node.resume_with(model, controller, gas, audit);
                 match result {
@@ -63,4 +75,12 @@ impl<M: 'static, C: 'static> BehaviorTree for Selector<M, C> {
     fn get_name(self: &Self) -> &String {
         &self.name
     }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        &self.nodes
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        &mut self.nodes
+    }
 }
@@ -1,34 +1,46 @@
 use super::super::*;
 
-pub struct Sequence<M, C> {
+pub struct Sequence<M, C, G = Option<i32>> {
     name: String,
-    nodes: Vec<Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>>,
+    nodes: Vec<BoxedBehaviorTree<G, M, C>>,
     index: Option<usize>,
+    gas_cost: u32,
 }
 
-impl<M, C> Sequence<M, C> {
-    pub fn new(nodes: Vec<Box<dyn BehaviorTree<Model = M, Controller = C> + Send + Sync>>) -> Self {
+impl<M, C, G> Sequence<M, C, G> {
+    pub fn new(nodes: Vec<BoxedBehaviorTree<G, M, C>>) -> Self {
         Sequence {
             name: get_bt_id(),
             nodes,
             index: None,
+            gas_cost: 0,
         }
     }
+
+    pub fn with_gas_cost(mut self, gas_cost: u32) -> Self {
+        self.gas_cost = gas_cost;
+        self
+    }
 }
 
-impl<M: 'static, C: 'static> BehaviorTree for Sequence<M, C> {
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for Sequence<M, C, G> {
     type Model = M;
     type Controller = C;
     fn resume_with(
         self: &mut Self,
         model: &Self::Model,
         controller: &mut Self::Controller,
-        gas: &mut Option<i32>,
+        gas: &mut G,
         mut audit: &mut Option<BehaviorTreeAudit>,
     ) -> BehaviorTreeState {
         audit.enter(self.get_name());
         let mut running_index = self.index.unwrap_or(0);
         loop {
+            if running_index < self.nodes.len() && !charge_gas(gas, self.gas_cost) {
+                self.index = Some(running_index);
+                audit.exit(self.get_name(), BehaviorTreeState::WaitingForGas);
+                return BehaviorTreeState::WaitingForGas;
+            }
             if let Some(node) = self.nodes.get_mut(running_index) {
                 let result = node.resume_with(model, controller, gas, audit);
                 match result {
@@ -50,6 +62,7 @@ impl<M: 'static, C: 'static> BehaviorTree for Sequence<M, C> {
                 }
             } else {
                 self.index = None;
+                audit.exit(self.get_name(), BehaviorTreeState::Complete);
                 return BehaviorTreeState::Complete;
             }
         }
@@ -62,4 +75,12 @@ impl<M: 'static, C: 'static> BehaviorTree for Sequence<M, C> {
     fn get_name(self: &Self) -> &String {
         &self.name
     }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        &self.nodes
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        &mut self.nodes
+    }
 }
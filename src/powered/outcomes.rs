@@ -0,0 +1,263 @@
+use super::{
+    BehaviorTreeDef, BehaviorTreeGasCosts, BehaviorTreePath, BehaviorTreeState, UserNodeDefinition,
+    UserWrapperDefinition,
+};
+
+// The states a node can return from a resume.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct BehaviorTreeOutcomes {
+    pub complete: bool,
+    pub fail: bool,
+    pub wait: bool,
+    pub wait_for_gas: bool,
+}
+
+impl BehaviorTreeOutcomes {
+    // Can return any state, the safe declaration for a leaf nothing is known about.
+    pub fn any() -> Self {
+        BehaviorTreeOutcomes {
+            complete: true,
+            fail: true,
+            wait: true,
+            wait_for_gas: true,
+        }
+    }
+
+    // Finishes within a single resume, either way.
+    pub fn finishes() -> Self {
+        BehaviorTreeOutcomes {
+            complete: true,
+            fail: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn can_return(&self, state: BehaviorTreeState) -> bool {
+        match state {
+            BehaviorTreeState::Complete => self.complete,
+            BehaviorTreeState::Failed => self.fail,
+            BehaviorTreeState::Waiting => self.wait,
+            BehaviorTreeState::WaitingForGas => self.wait_for_gas,
+        }
+    }
+
+    // Whether the node can ever end, i.e. complete or fail.
+    pub fn can_finish(&self) -> bool {
+        self.complete || self.fail
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BehaviorTreeNodeOutcomes {
+    pub path: BehaviorTreePath,
+    // The kind of the definition, e.g. "Selector".
+    pub kind: &'static str,
+    // Whether the node can run at all. Unreachable nodes still get the outcomes they would have.
+    pub reachable: bool,
+    pub outcomes: BehaviorTreeOutcomes,
+}
+
+// The outcomes of every node in a definition, in pre-order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BehaviorTreeOutcomeAnalysis {
+    nodes: Vec<BehaviorTreeNodeOutcomes>,
+}
+
+impl BehaviorTreeOutcomeAnalysis {
+    pub fn nodes(&self) -> &[BehaviorTreeNodeOutcomes] {
+        &self.nodes
+    }
+
+    pub fn get(&self, path: &[usize]) -> Option<&BehaviorTreeNodeOutcomes> {
+        self.nodes.iter().find(|node| node.path == path)
+    }
+
+    pub fn root(&self) -> BehaviorTreeOutcomes {
+        self.nodes[0].outcomes
+    }
+
+    // The topmost nodes that can never run; their descendants can't either.
+    pub fn dead_branches(&self) -> Vec<&BehaviorTreeNodeOutcomes> {
+        self.nodes
+            .iter()
+            .filter(|node| {
+                !node.reachable
+                    && self
+                        .get(&node.path[..node.path.len() - 1])
+                        .is_some_and(|parent| parent.reachable)
+            })
+            .collect()
+    }
+
+    // Reachable Selectors that always complete when they finish.
+    pub fn selectors_that_never_fail(&self) -> Vec<&BehaviorTreeNodeOutcomes> {
+        self.reachable()
+            .filter(|node| node.kind == "Selector" && !node.outcomes.fail)
+            .collect()
+    }
+
+    // Reachable repeats that can never finish although their child can, such as a
+    // RepeatUntilSuccess around a child that only fails. Those whose child can't wait loop
+    // forever within a single resume.
+    pub fn endless_loops(&self) -> Vec<&BehaviorTreeNodeOutcomes> {
+        self.reachable()
+            .filter(|node| node.kind.starts_with("Repeat") && !node.outcomes.can_finish())
+            .filter(|node| {
+                let child_path = [node.path.as_slice(), &[0]].concat();
+                self.get(&child_path)
+                    .is_some_and(|child| child.outcomes.can_finish())
+            })
+            .collect()
+    }
+
+    fn reachable(&self) -> impl Iterator<Item = &BehaviorTreeNodeOutcomes> {
+        self.nodes.iter().filter(|node| node.reachable)
+    }
+}
+
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    // Works out which states each node can return, given those of each user node, and those
+    // of each wrapper given its children's. Built-in nodes charge the default gas costs, as
+    // with create_tree, so any that enters a child can wait for gas. Under a budget that runs
+    // out by itself, like a Deadline, even free ones can.
+    pub fn analyze_outcomes(
+        &self,
+        user_outcomes: &impl Fn(&U) -> BehaviorTreeOutcomes,
+        wrapper_outcomes: &impl Fn(&W, &[BehaviorTreeOutcomes]) -> BehaviorTreeOutcomes,
+    ) -> BehaviorTreeOutcomeAnalysis {
+        self.analyze_outcomes_with_gas_costs(
+            &BehaviorTreeGasCosts::default(),
+            user_outcomes,
+            wrapper_outcomes,
+        )
+    }
+
+    // For trees created with create_tree_with_gas_costs.
+    pub fn analyze_outcomes_with_gas_costs(
+        &self,
+        gas_costs: &BehaviorTreeGasCosts,
+        user_outcomes: &impl Fn(&U) -> BehaviorTreeOutcomes,
+        wrapper_outcomes: &impl Fn(&W, &[BehaviorTreeOutcomes]) -> BehaviorTreeOutcomes,
+    ) -> BehaviorTreeOutcomeAnalysis {
+        let mut nodes = Vec::new();
+        self.analyze_outcomes_at(
+            &mut Vec::new(),
+            true,
+            gas_costs,
+            user_outcomes,
+            wrapper_outcomes,
+            &mut nodes,
+        );
+        BehaviorTreeOutcomeAnalysis { nodes }
+    }
+
+    fn analyze_outcomes_at(
+        &self,
+        path: &mut BehaviorTreePath,
+        reachable: bool,
+        gas_costs: &BehaviorTreeGasCosts,
+        user_outcomes: &impl Fn(&U) -> BehaviorTreeOutcomes,
+        wrapper_outcomes: &impl Fn(&W, &[BehaviorTreeOutcomes]) -> BehaviorTreeOutcomes,
+        nodes: &mut Vec<BehaviorTreeNodeOutcomes>,
+    ) -> BehaviorTreeOutcomes {
+        let slot = nodes.len();
+        nodes.push(BehaviorTreeNodeOutcomes {
+            path: path.clone(),
+            kind: self.kind_name(),
+            reachable,
+            outcomes: BehaviorTreeOutcomes::default(),
+        });
+        let mut children: Vec<BehaviorTreeOutcomes> = Vec::new();
+        for (index, child) in self.children().iter().enumerate() {
+            // A Sequence only moves on past completions, a Selector past failures.
+            let child_reachable = reachable
+                && match self {
+                    BehaviorTreeDef::Sequence(_) => children.iter().all(|child| child.complete),
+                    BehaviorTreeDef::Selector(_) => children.iter().all(|child| child.fail),
+                    BehaviorTreeDef::Repeat(_, 0) => false,
+                    _ => true,
+                };
+            path.push(index);
+            children.push(child.analyze_outcomes_at(
+                path,
+                child_reachable,
+                gas_costs,
+                user_outcomes,
+                wrapper_outcomes,
+                nodes,
+            ));
+            path.pop();
+        }
+        let mut outcomes = match self {
+            BehaviorTreeDef::Sequence(_) => composite(&children, true),
+            BehaviorTreeDef::Selector(_) => composite(&children, false),
+            BehaviorTreeDef::Repeat(_, 0) => BehaviorTreeOutcomes {
+                complete: true,
+                ..Default::default()
+            },
+            BehaviorTreeDef::Repeat(_, _) | BehaviorTreeDef::GasBudget(_, _) => children[0],
+            BehaviorTreeDef::RepeatUntilSuccess(_) => BehaviorTreeOutcomes {
+                complete: children[0].complete,
+                fail: false,
+                ..children[0]
+            },
+            BehaviorTreeDef::RepeatUntilFail(_) => BehaviorTreeOutcomes {
+                complete: children[0].fail,
+                fail: false,
+                ..children[0]
+            },
+            BehaviorTreeDef::Succeeder(_) => BehaviorTreeOutcomes {
+                complete: children[0].can_finish(),
+                fail: false,
+                ..children[0]
+            },
+            BehaviorTreeDef::Failer(_) => BehaviorTreeOutcomes {
+                complete: false,
+                fail: children[0].can_finish(),
+                ..children[0]
+            },
+            BehaviorTreeDef::Inverter(_) => BehaviorTreeOutcomes {
+                complete: children[0].fail,
+                fail: children[0].complete,
+                ..children[0]
+            },
+            BehaviorTreeDef::User(node_def) => user_outcomes(node_def),
+            BehaviorTreeDef::Wrapper(wrapper_def, _) => wrapper_outcomes(wrapper_def, &children),
+            BehaviorTreeDef::GasCost(_, _) => children[0],
+        };
+        // Gas is charged before entering a child, so only nodes that get that far can wait.
+        if gas_costs.of(self) > 0
+            && !self.children().is_empty()
+            && !matches!(self, BehaviorTreeDef::Repeat(_, 0))
+        {
+            outcomes.wait_for_gas = true;
+        }
+        nodes[slot].outcomes = outcomes;
+        outcomes
+    }
+}
+
+// A Sequence (sequence = true) runs children while they complete and fails with the first
+// failure; a Selector runs them while they fail and completes with the first completion.
+// Either waits whenever a child it reaches does.
+fn composite(children: &[BehaviorTreeOutcomes], sequence: bool) -> BehaviorTreeOutcomes {
+    let mut outcomes = BehaviorTreeOutcomes::default();
+    for child in children {
+        outcomes.wait |= child.wait;
+        outcomes.wait_for_gas |= child.wait_for_gas;
+        if sequence {
+            outcomes.fail |= child.fail;
+        } else {
+            outcomes.complete |= child.complete;
+        }
+        if !(if sequence { child.complete } else { child.fail }) {
+            return outcomes;
+        }
+    }
+    if sequence {
+        outcomes.complete = true;
+    } else {
+        outcomes.fail = true;
+    }
+    outcomes
+}
@@ -0,0 +1,131 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Clone)]
+pub enum BehaviorTreeValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl fmt::Display for BehaviorTreeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BehaviorTreeValue::Bool(value) => write!(f, "{}", value),
+            BehaviorTreeValue::Int(value) => write!(f, "{}", value),
+            BehaviorTreeValue::Float(value) => write!(f, "{}", value),
+            BehaviorTreeValue::Text(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+impl From<bool> for BehaviorTreeValue {
+    fn from(value: bool) -> Self {
+        BehaviorTreeValue::Bool(value)
+    }
+}
+
+impl From<i32> for BehaviorTreeValue {
+    fn from(value: i32) -> Self {
+        BehaviorTreeValue::Int(value as i64)
+    }
+}
+
+impl From<i64> for BehaviorTreeValue {
+    fn from(value: i64) -> Self {
+        BehaviorTreeValue::Int(value)
+    }
+}
+
+impl From<u32> for BehaviorTreeValue {
+    fn from(value: u32) -> Self {
+        BehaviorTreeValue::Int(value as i64)
+    }
+}
+
+impl From<usize> for BehaviorTreeValue {
+    fn from(value: usize) -> Self {
+        BehaviorTreeValue::Int(value as i64)
+    }
+}
+
+impl From<f32> for BehaviorTreeValue {
+    fn from(value: f32) -> Self {
+        BehaviorTreeValue::Float(value as f64)
+    }
+}
+
+impl From<f64> for BehaviorTreeValue {
+    fn from(value: f64) -> Self {
+        BehaviorTreeValue::Float(value)
+    }
+}
+
+impl From<&str> for BehaviorTreeValue {
+    fn from(value: &str) -> Self {
+        BehaviorTreeValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for BehaviorTreeValue {
+    fn from(value: String) -> Self {
+        BehaviorTreeValue::Text(value)
+    }
+}
+
+// Structured details attached to an audit marker, e.g. why a leaf decided to fail.
+//
+// BehaviorTreePayload::new("target out of range")
+//     .with("distance", 12.3)
+//     .with("max", 10.0)
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BehaviorTreePayload {
+    pub message: String,
+    pub fields: Vec<(String, BehaviorTreeValue)>,
+}
+
+impl BehaviorTreePayload {
+    pub fn new(message: impl Into<String>) -> Self {
+        BehaviorTreePayload {
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<BehaviorTreeValue>) -> Self {
+        self.fields.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BehaviorTreeValue> {
+        self.fields
+            .iter()
+            .find(|(field_key, _)| field_key == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.message.is_empty() && self.fields.is_empty()
+    }
+}
+
+impl fmt::Display for BehaviorTreePayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        for (field_index, (key, value)) in self.fields.iter().enumerate() {
+            match (field_index, self.message.is_empty()) {
+                (0, true) => write!(f, "{}={}", key, value)?,
+                (0, false) => write!(f, " ({}={}", key, value)?,
+                (_, _) => write!(f, ", {}={}", key, value)?,
+            }
+        }
+        if !self.message.is_empty() && !self.fields.is_empty() {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{BehaviorTree, BehaviorTreeAudit, BehaviorTreeState, BoxedBehaviorTree, Gas};
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct BehaviorTreeNodeStats {
+    pub entries: u64,
+    pub complete: u64,
+    pub failed: u64,
+    pub waiting: u64,
+    pub waiting_for_gas: u64,
+    // Gas consumed while inside the node, including its children.
+    // Entries made without a gas limit do not contribute.
+    pub gas_consumed: f64,
+    // Wall-clock time spent inside the node, including its children.
+    pub elapsed: Duration,
+}
+
+impl BehaviorTreeNodeStats {
+    fn record(&mut self, state: BehaviorTreeState, gas_consumed: f64, elapsed: Duration) {
+        self.entries += 1;
+        match state {
+            BehaviorTreeState::Complete => self.complete += 1,
+            BehaviorTreeState::Failed => self.failed += 1,
+            BehaviorTreeState::Waiting => self.waiting += 1,
+            BehaviorTreeState::WaitingForGas => self.waiting_for_gas += 1,
+        }
+        self.gas_consumed += gas_consumed;
+        self.elapsed += elapsed;
+    }
+}
+
+// A shared collector of per-node statistics. Clones refer to the same statistics,
+// so a single profiler can be handed to every node of a tree, or to many trees.
+#[derive(Clone, Default)]
+pub struct BehaviorTreeProfiler {
+    stats: Arc<Mutex<HashMap<String, BehaviorTreeNodeStats>>>,
+}
+
+impl BehaviorTreeProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stats(&self, node_name: &str) -> Option<BehaviorTreeNodeStats> {
+        self.stats.lock().unwrap().get(node_name).copied()
+    }
+
+    // Every profiled node, most expensive first: by gas consumed, then by wall-clock time.
+    pub fn report(&self) -> BehaviorTreeProfile {
+        let mut nodes: Vec<(String, BehaviorTreeNodeStats)> = self
+            .stats
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, stats)| (name.clone(), *stats))
+            .collect();
+        nodes.sort_by(|(a_name, a), (b_name, b)| {
+            b.gas_consumed
+                .total_cmp(&a.gas_consumed)
+                .then(b.elapsed.cmp(&a.elapsed))
+                .then(a_name.cmp(b_name))
+        });
+        BehaviorTreeProfile { nodes }
+    }
+
+    pub fn clear(&self) {
+        self.stats.lock().unwrap().clear();
+    }
+
+    fn record(
+        &self,
+        node_name: &str,
+        state: BehaviorTreeState,
+        gas_consumed: f64,
+        elapsed: Duration,
+    ) {
+        let mut stats = self.stats.lock().unwrap();
+        match stats.get_mut(node_name) {
+            Some(node_stats) => node_stats.record(state, gas_consumed, elapsed),
+            None => {
+                let mut node_stats = BehaviorTreeNodeStats::default();
+                node_stats.record(state, gas_consumed, elapsed);
+                stats.insert(node_name.to_string(), node_stats);
+            }
+        }
+    }
+}
+
+pub struct BehaviorTreeProfile {
+    pub nodes: Vec<(String, BehaviorTreeNodeStats)>,
+}
+
+impl fmt::Display for BehaviorTreeProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:>8} {:>8} {:>8} {:>8} {:>8} {:>10} {:>12}",
+            "node", "entries", "complete", "failed", "waiting", "gas wait", "gas", "time"
+        )?;
+        for (name, stats) in self.nodes.iter() {
+            writeln!(
+                f,
+                "{:<24} {:>8} {:>8} {:>8} {:>8} {:>8} {:>10.1} {:>12?}",
+                name,
+                stats.entries,
+                stats.complete,
+                stats.failed,
+                stats.waiting,
+                stats.waiting_for_gas,
+                stats.gas_consumed,
+                stats.elapsed
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Wraps a node, recording its statistics under the node's own name.
+pub struct Profiled<M, C, G = Option<i32>> {
+    node: BoxedBehaviorTree<G, M, C>,
+    profiler: BehaviorTreeProfiler,
+}
+
+impl<M, C, G> Profiled<M, C, G> {
+    pub fn new(node: BoxedBehaviorTree<G, M, C>, profiler: BehaviorTreeProfiler) -> Self {
+        Profiled { node, profiler }
+    }
+}
+
+impl<M: 'static, C: 'static, G: Gas> BehaviorTree<G> for Profiled<M, C, G> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        gas: &mut G,
+        audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        let gas_before = gas.remaining();
+        let started = Instant::now();
+        let result = self.node.resume_with(model, controller, gas, audit);
+        let elapsed = started.elapsed();
+        let gas_consumed = match (gas_before, gas.remaining()) {
+            (Some(before), Some(after)) => before - after,
+            _ => 0.0,
+        };
+        self.profiler
+            .record(self.node.get_name(), result, gas_consumed, elapsed);
+        result
+    }
+
+    fn reset(self: &mut Self, model: &Self::Model) {
+        self.node.reset(model);
+    }
+
+    fn get_name(self: &Self) -> &String {
+        self.node.get_name()
+    }
+
+    fn children(self: &Self) -> &[BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        self.node.children()
+    }
+
+    fn children_mut(self: &mut Self) -> &mut [BoxedBehaviorTree<G, Self::Model, Self::Controller>] {
+        self.node.children_mut()
+    }
+}
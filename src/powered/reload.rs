@@ -0,0 +1,188 @@
+use std::mem;
+
+use super::tree_def::audit_user_nodes;
+use super::{
+    BehaviorTreeCreateError, BehaviorTreeDef, BehaviorTreeGasCosts, BehaviorTreePath,
+    BehaviorTreeProfiler, BehaviorTreeState, BoxedBehaviorTreeFor, Profiled, UserNodeDefinition,
+    UserWrapperDefinition,
+};
+
+// What reloading a live tree had to create afresh. Every other node is the one from the live
+// tree, carrying on from where it was.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BehaviorTreeReload {
+    // Paths in the new definition of the nodes created afresh, parents before children.
+    pub recreated: Vec<BehaviorTreePath>,
+}
+
+impl BehaviorTreeReload {
+    pub fn is_unchanged(&self) -> bool {
+        self.recreated.is_empty()
+    }
+
+    // Whether the reload may have cut short a run, given the state the tree last returned.
+    // Trees that were waiting had nodes part way through; trees that finished have been
+    // reset and start over anyway.
+    pub fn disrupts(&self, last_state: Option<BehaviorTreeState>) -> bool {
+        matches!(
+            last_state,
+            Some(BehaviorTreeState::Waiting | BehaviorTreeState::WaitingForGas)
+        ) && !self.is_unchanged()
+    }
+}
+
+impl<U, W> BehaviorTreeDef<U, W>
+where
+    U: UserNodeDefinition + PartialEq,
+    W: UserWrapperDefinition<U> + PartialEq,
+{
+    // Swaps a live tree created from old_def over to this definition, keeping its state where
+    // the two agree. Subtrees whose definitions are unchanged are kept whole. Other nodes are
+    // kept, with their indices and repeat counts, if they are the same kind with the same
+    // arguments and number of children; the rest are created afresh, while their children are
+    // still matched up by index. If creating the new nodes fails, the tree is left as it was.
+    pub fn try_reload_tree(
+        &self,
+        old_def: &BehaviorTreeDef<U, W>,
+        tree: &mut BoxedBehaviorTreeFor<U>,
+    ) -> Result<BehaviorTreeReload, BehaviorTreeCreateError> {
+        self.try_reload_tree_with_gas_costs(old_def, tree, &BehaviorTreeGasCosts::default())
+    }
+
+    // For trees created with create_tree_with_gas_costs. Kept nodes keep their old costs.
+    pub fn try_reload_tree_with_gas_costs(
+        &self,
+        old_def: &BehaviorTreeDef<U, W>,
+        tree: &mut BoxedBehaviorTreeFor<U>,
+        gas_costs: &BehaviorTreeGasCosts,
+    ) -> Result<BehaviorTreeReload, BehaviorTreeCreateError> {
+        self.try_reload_tree_decorated(old_def, tree, gas_costs, &|_, node| node)
+    }
+
+    // For trees created with create_profiled_tree_with_gas_costs.
+    pub fn try_reload_profiled_tree_with_gas_costs(
+        &self,
+        old_def: &BehaviorTreeDef<U, W>,
+        tree: &mut BoxedBehaviorTreeFor<U>,
+        profiler: &BehaviorTreeProfiler,
+        gas_costs: &BehaviorTreeGasCosts,
+    ) -> Result<BehaviorTreeReload, BehaviorTreeCreateError> {
+        self.try_reload_tree_decorated(old_def, tree, gas_costs, &|_, node| {
+            Box::new(Profiled::new(node, profiler.clone()))
+        })
+    }
+
+    // For trees created with create_audited_tree.
+    pub fn try_reload_audited_tree(
+        &self,
+        old_def: &BehaviorTreeDef<U, W>,
+        tree: &mut BoxedBehaviorTreeFor<U>,
+    ) -> Result<BehaviorTreeReload, BehaviorTreeCreateError> {
+        self.try_reload_tree_decorated(
+            old_def,
+            tree,
+            &BehaviorTreeGasCosts::default(),
+            &audit_user_nodes,
+        )
+    }
+
+    fn try_reload_tree_decorated(
+        &self,
+        old_def: &BehaviorTreeDef<U, W>,
+        tree: &mut BoxedBehaviorTreeFor<U>,
+        gas_costs: &BehaviorTreeGasCosts,
+        decorate: &dyn Fn(&Self, BoxedBehaviorTreeFor<U>) -> BoxedBehaviorTreeFor<U>,
+    ) -> Result<BehaviorTreeReload, BehaviorTreeCreateError> {
+        // Everything that can fail happens up front; nodes that turn out to be kept are
+        // dropped from the new tree instead.
+        let mut new_tree = self.try_create_tree_decorated(gas_costs, decorate, &mut Vec::new())?;
+        let mut reload = BehaviorTreeReload::default();
+        self.transplant(old_def, &mut new_tree, tree, &mut Vec::new(), &mut reload);
+        *tree = new_tree;
+        Ok(reload)
+    }
+
+    // Moves old nodes into the new tree wherever they can carry on. new_node was created
+    // from this definition and old_node from old_def; whatever isn't kept ends up in old_node.
+    fn transplant(
+        &self,
+        old_def: &BehaviorTreeDef<U, W>,
+        new_node: &mut BoxedBehaviorTreeFor<U>,
+        old_node: &mut BoxedBehaviorTreeFor<U>,
+        path: &mut BehaviorTreePath,
+        reload: &mut BehaviorTreeReload,
+    ) {
+        if self == old_def {
+            mem::swap(new_node, old_node);
+            return;
+        }
+        // Children can only be matched up if both nodes give them out, like built-in nodes do.
+        let count = self.children().len();
+        let matched = if new_node.children_mut().len() == count
+            && old_node.children_mut().len() == old_def.children().len()
+        {
+            count.min(old_def.children().len())
+        } else {
+            0
+        };
+        if self.same_node(old_def) && matched == count {
+            // Keep the old node, but hand it the new children to match against the old ones.
+            mem::swap(new_node, old_node);
+            for index in 0..count {
+                mem::swap(
+                    &mut new_node.children_mut()[index],
+                    &mut old_node.children_mut()[index],
+                );
+            }
+        } else {
+            reload.recreated.push(path.clone());
+        }
+        for index in 0..matched {
+            path.push(index);
+            self.children()[index].transplant(
+                &old_def.children()[index],
+                &mut new_node.children_mut()[index],
+                &mut old_node.children_mut()[index],
+                path,
+                reload,
+            );
+            path.pop();
+        }
+        for index in matched..count {
+            path.push(index);
+            self.children()[index].visit(&mut |child_path, _| {
+                reload
+                    .recreated
+                    .push(path.iter().chain(child_path).copied().collect());
+            });
+            path.pop();
+        }
+    }
+
+    // Whether a node created from old_def holds state that still makes sense for this one.
+    fn same_node(&self, old_def: &BehaviorTreeDef<U, W>) -> bool {
+        if self.children().len() != old_def.children().len() {
+            return false;
+        }
+        match (self, old_def) {
+            (BehaviorTreeDef::Repeat(_, repeats), BehaviorTreeDef::Repeat(_, old_repeats)) => {
+                repeats == old_repeats
+            }
+            (BehaviorTreeDef::GasCost(_, gas_cost), BehaviorTreeDef::GasCost(_, old_gas_cost)) => {
+                gas_cost == old_gas_cost
+            }
+            (
+                BehaviorTreeDef::GasBudget(_, allowance),
+                BehaviorTreeDef::GasBudget(_, old_allowance),
+            ) => allowance == old_allowance,
+            (BehaviorTreeDef::User(node_def), BehaviorTreeDef::User(old_node_def)) => {
+                node_def == old_node_def
+            }
+            (
+                BehaviorTreeDef::Wrapper(wrapper_def, _),
+                BehaviorTreeDef::Wrapper(old_wrapper_def, _),
+            ) => wrapper_def == old_wrapper_def,
+            _ => self.kind_name() == old_def.kind_name(),
+        }
+    }
+}
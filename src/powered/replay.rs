@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use super::{
+    BehaviorTreeAudit, BehaviorTreeIndex, BehaviorTreeMarker, BehaviorTreePath,
+    BehaviorTreePayload, BehaviorTreeState,
+};
+
+// Frames are saved every so many events, so stepping backward doesn't replay from the start.
+const CHECKPOINT_INTERVAL: usize = 256;
+
+// The reconstructed state of a tree after some prefix of an audit's events.
+#[derive(Clone, Default)]
+pub struct BehaviorTreeReplayFrame {
+    // The paths of the entered nodes, from the outermost to the innermost.
+    pub active_path: Vec<BehaviorTreePath>,
+    // The outcome of each node the last time it exited.
+    pub outcomes: HashMap<BehaviorTreePath, BehaviorTreeState>,
+    // The markers of each node since it was last entered.
+    pub markers: HashMap<BehaviorTreePath, Vec<BehaviorTreePayload>>,
+    // The tick being replayed, if a tick boundary has been seen.
+    pub tick: Option<u64>,
+}
+
+impl BehaviorTreeReplayFrame {
+    pub fn is_active(&self, path: &[usize]) -> bool {
+        self.active_path.iter().any(|active| active == path)
+    }
+
+    pub fn outcome(&self, path: &[usize]) -> Option<BehaviorTreeState> {
+        self.outcomes.get(path).copied()
+    }
+
+    pub fn markers(&self, path: &[usize]) -> &[BehaviorTreePayload] {
+        self.markers
+            .get(path)
+            .map(|markers| markers.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn apply(&mut self, event: &BehaviorTreeMarker, path: &Option<BehaviorTreePath>) {
+        match (event, path) {
+            (BehaviorTreeMarker::Tick(tick), _) => {
+                self.active_path.clear();
+                self.tick = Some(*tick);
+            }
+            (BehaviorTreeMarker::Enter(_), Some(path)) => {
+                self.markers.remove(path);
+                self.active_path.push(path.clone());
+            }
+            (BehaviorTreeMarker::Marker(_), Some(path)) => {
+                self.markers
+                    .entry(path.clone())
+                    .or_default()
+                    .push(BehaviorTreePayload::default());
+            }
+            (BehaviorTreeMarker::MarkerWithPayload(_, payload), Some(path)) => {
+                self.markers
+                    .entry(path.clone())
+                    .or_default()
+                    .push(payload.clone());
+            }
+            (BehaviorTreeMarker::Exit(_, state), Some(path)) => {
+                if let Some(position) = self.active_path.iter().rposition(|active| active == path) {
+                    self.active_path.truncate(position);
+                }
+                self.outcomes.insert(path.clone(), *state);
+            }
+            // Events of nodes missing from the index can't be placed in the tree.
+            (_, None) => {}
+        }
+    }
+}
+
+// Steps through a recorded audit, reconstructing the tree's state at any event.
+// The index must come from the tree that produced the audit; its paths can be looked up
+// in the definition with BehaviorTreeDef::get. To replay offline, serialize the index with
+// the audit.
+pub struct BehaviorTreeReplay {
+    events: Vec<BehaviorTreeMarker>,
+    paths: Vec<Option<BehaviorTreePath>>,
+    position: usize,
+    frame: BehaviorTreeReplayFrame,
+    checkpoints: Vec<BehaviorTreeReplayFrame>,
+}
+
+impl BehaviorTreeReplay {
+    pub fn new(audit: &BehaviorTreeAudit, index: &BehaviorTreeIndex) -> Self {
+        BehaviorTreeReplay {
+            events: audit.events().cloned().collect(),
+            paths: index.trace(audit.events()),
+            position: 0,
+            frame: BehaviorTreeReplayFrame::default(),
+            checkpoints: vec![BehaviorTreeReplayFrame::default()],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    // The number of events applied to the current frame, from 0 to len().
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn frame(&self) -> &BehaviorTreeReplayFrame {
+        &self.frame
+    }
+
+    // The most recently applied event and the path of its node.
+    pub fn current_event(&self) -> Option<(&BehaviorTreeMarker, Option<&BehaviorTreePath>)> {
+        let index = self.position.checked_sub(1)?;
+        Some((&self.events[index], self.paths[index].as_ref()))
+    }
+
+    // Applies the next event. Returns false at the end of the audit.
+    pub fn step_forward(&mut self) -> bool {
+        if self.position >= self.events.len() {
+            return false;
+        }
+        self.frame
+            .apply(&self.events[self.position], &self.paths[self.position]);
+        self.position += 1;
+        if self.position.is_multiple_of(CHECKPOINT_INTERVAL)
+            && self.checkpoints.len() == self.position / CHECKPOINT_INTERVAL
+        {
+            self.checkpoints.push(self.frame.clone());
+        }
+        true
+    }
+
+    // Un-applies the last event. Returns false at the start of the audit.
+    pub fn step_backward(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.seek(self.position - 1);
+        true
+    }
+
+    // Moves to the frame after the given number of events, clamped to the audit's length.
+    pub fn seek(&mut self, position: usize) -> &BehaviorTreeReplayFrame {
+        let position = position.min(self.events.len());
+        if position < self.position {
+            let checkpoint = (position / CHECKPOINT_INTERVAL).min(self.checkpoints.len() - 1);
+            self.frame = self.checkpoints[checkpoint].clone();
+            self.position = checkpoint * CHECKPOINT_INTERVAL;
+        }
+        while self.position < position {
+            self.step_forward();
+        }
+        &self.frame
+    }
+
+    // Moves to the first event of the given tick, if it was retained.
+    pub fn seek_tick(&mut self, tick: u64) -> Option<&BehaviorTreeReplayFrame> {
+        let position = self.events.iter().position(
+            |event| matches!(event, BehaviorTreeMarker::Tick(event_tick) if *event_tick == tick),
+        )?;
+        Some(self.seek(position + 1))
+    }
+}
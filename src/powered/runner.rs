@@ -0,0 +1,179 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{
+    BehaviorTree, BehaviorTreeAudit, BehaviorTreeAuditLimit, BehaviorTreeState, BoxedBehaviorTree,
+    Gas,
+};
+
+// What a runner does once its root completes or fails.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum RootPolicy {
+    // Stop after the root completes or fails.
+    RunOnce,
+    // Reset and start the root again, whatever its result.
+    Loop,
+    // Reset and start the root again if it failed, stop once it completes.
+    RestartOnFailure,
+}
+
+// Refills a budget given the number of the tick about to run.
+pub type GasRefillFn<G> = Box<dyn FnMut(&mut G, u64) + Send + Sync>;
+
+// How a runner refills its gas at the start of each tick.
+pub enum GasRefill<G> {
+    // Keep whatever is left. The caller tops it up through TreeRunner::gas_mut.
+    Keep,
+    // Reset the budget to this value, restarting it with Gas::restart, so a Deadline made
+    // with after gives every tick the same time.
+    Reset(G),
+    // Refill with a function of the budget and the number of the tick about to run.
+    Custom(GasRefillFn<G>),
+}
+
+// What a runner does with its audit.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum AuditPolicy {
+    Off,
+    // Keep only the most recent tick.
+    PerTick,
+    // Keep history across ticks, up to the limit.
+    Retain(BehaviorTreeAuditLimit),
+}
+
+// Owns a tree and the loop around it: refilling gas, resuming the root once per tick,
+// restarting it according to the root policy, and recording audits.
+pub struct TreeRunner<M, C, G = Option<i32>> {
+    tree: BoxedBehaviorTree<G, M, C>,
+    root_policy: RootPolicy,
+    gas: G,
+    gas_refill: GasRefill<G>,
+    audit_policy: AuditPolicy,
+    audit: Option<BehaviorTreeAudit>,
+    ticks: u64,
+    last_state: Option<BehaviorTreeState>,
+    finished: bool,
+}
+
+impl<M: 'static, C: 'static, G: Gas + Clone> TreeRunner<M, C, G> {
+    // A runner that loops forever, keeps its gas between ticks and doesn't audit.
+    pub fn new(tree: BoxedBehaviorTree<G, M, C>, gas: G) -> Self {
+        TreeRunner {
+            tree,
+            root_policy: RootPolicy::Loop,
+            gas,
+            gas_refill: GasRefill::Keep,
+            audit_policy: AuditPolicy::Off,
+            audit: None,
+            ticks: 0,
+            last_state: None,
+            finished: false,
+        }
+    }
+
+    pub fn with_root_policy(mut self, root_policy: RootPolicy) -> Self {
+        self.root_policy = root_policy;
+        self
+    }
+
+    pub fn with_gas_refill(mut self, gas_refill: GasRefill<G>) -> Self {
+        self.gas_refill = gas_refill;
+        self
+    }
+
+    pub fn with_audit(mut self, audit_policy: AuditPolicy) -> Self {
+        self.audit_policy = audit_policy;
+        self.audit = match audit_policy {
+            AuditPolicy::Off => None,
+            AuditPolicy::PerTick => Some(BehaviorTreeAudit::new()),
+            AuditPolicy::Retain(limit) => Some(BehaviorTreeAudit::bounded(limit)),
+        };
+        self
+    }
+
+    // Runs one tick, returning the root's state. Once finished, the final state is
+    // returned without resuming the tree.
+    pub fn tick(&mut self, model: &M, controller: &mut C) -> BehaviorTreeState {
+        if let Some(last_state) = self.last_state.filter(|_| self.finished) {
+            return last_state;
+        }
+        match &mut self.gas_refill {
+            GasRefill::Keep => {}
+            GasRefill::Reset(gas) => {
+                self.gas = gas.clone();
+                self.gas.restart();
+            }
+            GasRefill::Custom(refill) => refill(&mut self.gas, self.ticks + 1),
+        }
+        if let Some(audit) = &mut self.audit {
+            if self.audit_policy == AuditPolicy::PerTick {
+                audit.clear();
+            }
+            audit.start_tick();
+        }
+        let state = self
+            .tree
+            .resume_with(model, controller, &mut self.gas, &mut self.audit);
+        self.ticks += 1;
+        self.last_state = Some(state);
+        match (state, self.root_policy) {
+            (BehaviorTreeState::Complete, RootPolicy::Loop)
+            | (BehaviorTreeState::Failed, RootPolicy::Loop)
+            | (BehaviorTreeState::Failed, RootPolicy::RestartOnFailure) => self.tree.reset(model),
+            (BehaviorTreeState::Complete, _) | (BehaviorTreeState::Failed, _) => {
+                self.finished = true
+            }
+            _ => {}
+        }
+        state
+    }
+
+    // Resets the tree and clears the finished flag, so the next tick starts from the root.
+    pub fn restart(&mut self, model: &M) {
+        self.tree.reset(model);
+        self.finished = false;
+        self.last_state = None;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // The number of ticks run so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn last_state(&self) -> Option<BehaviorTreeState> {
+        self.last_state
+    }
+
+    pub fn gas(&self) -> &G {
+        &self.gas
+    }
+
+    pub fn gas_mut(&mut self) -> &mut G {
+        &mut self.gas
+    }
+
+    pub fn audit(&self) -> Option<&BehaviorTreeAudit> {
+        self.audit.as_ref()
+    }
+
+    pub fn take_audit(&mut self) -> Option<BehaviorTreeAudit> {
+        let audit = self.audit.as_mut()?;
+        let taken = audit.clone();
+        audit.clear();
+        Some(taken)
+    }
+
+    pub fn tree(&self) -> &(dyn BehaviorTree<G, Model = M, Controller = C> + Send + Sync) {
+        self.tree.as_ref()
+    }
+
+    pub fn tree_mut(&mut self) -> &mut BoxedBehaviorTree<G, M, C> {
+        &mut self.tree
+    }
+}
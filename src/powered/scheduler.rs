@@ -0,0 +1,254 @@
+use super::{
+    BehaviorTreeCreateError, BehaviorTreeReload, BehaviorTreeState, BoxedBehaviorTree, Gas,
+};
+
+// The order agents are run in, and how a frame's budget is split between them.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SchedulingOrder {
+    // Agents share the budget equally, starting one agent further along each frame.
+    RoundRobin,
+    // Agents run highest priority first, with budget shares weighted by priority.
+    Priority,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct BehaviorTreeAgentStats {
+    pub frames_run: u64,
+    // Frames in which the agent got no gas, or ran out of it.
+    pub frames_starved: u64,
+    pub consecutive_frames_starved: u64,
+    pub max_consecutive_frames_starved: u64,
+}
+
+impl BehaviorTreeAgentStats {
+    fn record(&mut self, starved: bool) {
+        if starved {
+            self.frames_starved += 1;
+            self.consecutive_frames_starved += 1;
+            self.max_consecutive_frames_starved = self
+                .max_consecutive_frames_starved
+                .max(self.consecutive_frames_starved);
+        } else {
+            self.consecutive_frames_starved = 0;
+        }
+    }
+}
+
+pub struct BehaviorTreeFrameReport<H> {
+    // The number of agents resumed this frame.
+    pub ran: usize,
+    // The agents that got no gas, or ran out of it.
+    pub starved: Vec<H>,
+}
+
+struct ScheduledAgent<H, M, C, G> {
+    handle: H,
+    tree: BoxedBehaviorTree<G, M, C>,
+    priority: u32,
+    waiting_for_gas: bool,
+    last_state: Option<BehaviorTreeState>,
+    stats: BehaviorTreeAgentStats,
+}
+
+// Ticks many agents' trees per frame out of one shared gas budget.
+//
+// Agents are identified by a handle the caller uses to find each agent's model and
+// controller. Agents that stopped waiting for gas run first in the next frame.
+// Roots that complete or fail are reset and start again.
+pub struct BehaviorTreeScheduler<H, M, C, G = Option<i32>> {
+    agents: Vec<ScheduledAgent<H, M, C, G>>,
+    order: SchedulingOrder,
+    cursor: usize,
+    frames: u64,
+}
+
+impl<H: Clone + PartialEq, M: 'static, C: 'static, G: Gas> BehaviorTreeScheduler<H, M, C, G> {
+    pub fn new(order: SchedulingOrder) -> Self {
+        BehaviorTreeScheduler {
+            agents: Vec::new(),
+            order,
+            cursor: 0,
+            frames: 0,
+        }
+    }
+
+    // Adds an agent. Priorities only matter for SchedulingOrder::Priority; 0 counts as 1.
+    pub fn add_agent(&mut self, handle: H, tree: BoxedBehaviorTree<G, M, C>, priority: u32) {
+        self.agents.push(ScheduledAgent {
+            handle,
+            tree,
+            priority,
+            waiting_for_gas: false,
+            last_state: None,
+            stats: BehaviorTreeAgentStats::default(),
+        });
+    }
+
+    pub fn remove_agent(&mut self, handle: &H) -> Option<BoxedBehaviorTree<G, M, C>> {
+        let index = self
+            .agents
+            .iter()
+            .position(|agent| agent.handle == *handle)?;
+        Some(self.agents.remove(index).tree)
+    }
+
+    pub fn set_priority(&mut self, handle: &H, priority: u32) {
+        if let Some(agent) = self.agents.iter_mut().find(|agent| agent.handle == *handle) {
+            agent.priority = priority;
+        }
+    }
+
+    // Reloads the given agents' trees, usually with |tree| new_def.try_reload_tree(&old_def,
+    // tree), and returns the agents the reload disrupted part way through a run. Stops at the
+    // first error, by which point earlier agents have already been reloaded.
+    pub fn reload_agents(
+        &mut self,
+        handles: &[H],
+        mut reload: impl FnMut(
+            &mut BoxedBehaviorTree<G, M, C>,
+        ) -> Result<BehaviorTreeReload, BehaviorTreeCreateError>,
+    ) -> Result<Vec<H>, BehaviorTreeCreateError> {
+        let mut disrupted = Vec::new();
+        for agent in self
+            .agents
+            .iter_mut()
+            .filter(|agent| handles.contains(&agent.handle))
+        {
+            if reload(&mut agent.tree)?.disrupts(agent.last_state) {
+                disrupted.push(agent.handle.clone());
+            }
+        }
+        Ok(disrupted)
+    }
+
+    pub fn len(&self) -> usize {
+        self.agents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.agents.is_empty()
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn stats(&self, handle: &H) -> Option<BehaviorTreeAgentStats> {
+        self.agents
+            .iter()
+            .find(|agent| agent.handle == *handle)
+            .map(|agent| agent.stats)
+    }
+
+    pub fn last_state(&self, handle: &H) -> Option<BehaviorTreeState> {
+        self.agents
+            .iter()
+            .find(|agent| agent.handle == *handle)
+            .and_then(|agent| agent.last_state)
+    }
+
+    // Every agent's handle and statistics, most starved first.
+    pub fn starvation_report(&self) -> Vec<(H, BehaviorTreeAgentStats)> {
+        let mut report: Vec<(H, BehaviorTreeAgentStats)> = self
+            .agents
+            .iter()
+            .map(|agent| (agent.handle.clone(), agent.stats))
+            .collect();
+        report.sort_by(|(_, a), (_, b)| {
+            b.consecutive_frames_starved
+                .cmp(&a.consecutive_frames_starved)
+                .then(b.frames_starved.cmp(&a.frames_starved))
+        });
+        report
+    }
+
+    // Runs one frame, splitting the budget between the agents. For each agent, run is called
+    // with its handle and a function to call with that agent's model and controller.
+    // Agents whose run doesn't call that function are skipped for the frame.
+    pub fn tick_frame(
+        &mut self,
+        budget: &mut G,
+        mut run: impl FnMut(&H, &mut dyn FnMut(&M, &mut C)),
+    ) -> BehaviorTreeFrameReport<H> {
+        let order = self.frame_order();
+        let mut weight_left: u64 = order
+            .iter()
+            .map(|index| self.weight(&self.agents[*index]))
+            .sum();
+        let mut report = BehaviorTreeFrameReport {
+            ran: 0,
+            starved: Vec::new(),
+        };
+        for index in order {
+            let weight = self.weight(&self.agents[index]);
+            let agent = &mut self.agents[index];
+            let share = match budget.remaining() {
+                Some(remaining) if remaining > 0.0 => {
+                    (remaining * weight as f64 / weight_left as f64).ceil() as u32
+                }
+                Some(_) => 0,
+                None => u32::MAX,
+            };
+            weight_left -= weight;
+            if share == 0 || !budget.has_gas() {
+                agent.waiting_for_gas = true;
+                agent.stats.record(true);
+                report.starved.push(agent.handle.clone());
+                continue;
+            }
+            let mut allowance = budget.carve(share);
+            let mut state = None;
+            run(&agent.handle, &mut |model, controller| {
+                let result = agent
+                    .tree
+                    .resume_with(model, controller, &mut allowance, &mut None);
+                if let BehaviorTreeState::Complete | BehaviorTreeState::Failed = result {
+                    agent.tree.reset(model);
+                }
+                state = Some(result);
+            });
+            budget.refund(allowance);
+            let Some(state) = state else {
+                continue;
+            };
+            let starved = state == BehaviorTreeState::WaitingForGas;
+            agent.last_state = Some(state);
+            agent.waiting_for_gas = starved;
+            agent.stats.frames_run += 1;
+            agent.stats.record(starved);
+            report.ran += 1;
+            if starved {
+                report.starved.push(agent.handle.clone());
+            }
+        }
+        self.frames += 1;
+        if !self.agents.is_empty() {
+            self.cursor = (self.cursor + 1) % self.agents.len();
+        }
+        report
+    }
+
+    fn weight(&self, agent: &ScheduledAgent<H, M, C, G>) -> u64 {
+        match self.order {
+            SchedulingOrder::RoundRobin => 1,
+            SchedulingOrder::Priority => agent.priority.max(1) as u64,
+        }
+    }
+
+    // Agents left waiting for gas go first, then the rest, each group in scheduling order.
+    fn frame_order(&self) -> Vec<usize> {
+        let count = self.agents.len();
+        let mut order: Vec<usize> = match self.order {
+            SchedulingOrder::RoundRobin => (0..count)
+                .map(|offset| (self.cursor + offset) % count)
+                .collect(),
+            SchedulingOrder::Priority => {
+                let mut order: Vec<usize> = (0..count).collect();
+                order.sort_by(|a, b| self.agents[*b].priority.cmp(&self.agents[*a].priority));
+                order
+            }
+        };
+        order.sort_by_key(|index| !self.agents[*index].waiting_for_gas);
+        order
+    }
+}
@@ -0,0 +1,90 @@
+use serde_json::{json, Value};
+
+use super::{BehaviorTreeDef, UserNodeDefinition, UserWrapperDefinition};
+
+// The serde shape of a definition variant's contents.
+pub(crate) enum SchemaVariant {
+    Children,
+    Child,
+    // A child followed by a count of up to the given maximum.
+    ChildAndCount(u64),
+    User,
+    Wrapper,
+}
+
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    // A JSON Schema (draft 2020-12) for definitions as serde_json writes them, e.g.
+    // {"Sequence": [{"User": ...}, {"Repeat": [{"User": ...}, 3]}]}. User nodes and wrappers
+    // are checked against the given schemas, which are embedded under $defs, so any $ref
+    // inside them should be resolved against an $id of their own.
+    pub fn json_schema(user_schema: Value, wrapper_schema: Value) -> Value {
+        json_schema(
+            "BehaviorTreeDef",
+            &[
+                ("Sequence", SchemaVariant::Children),
+                ("Selector", SchemaVariant::Children),
+                ("Repeat", SchemaVariant::ChildAndCount(usize::MAX as u64)),
+                ("RepeatUntilSuccess", SchemaVariant::Child),
+                ("RepeatUntilFail", SchemaVariant::Child),
+                ("Succeeder", SchemaVariant::Child),
+                ("Failer", SchemaVariant::Child),
+                ("Inverter", SchemaVariant::Child),
+                ("User", SchemaVariant::User),
+                ("Wrapper", SchemaVariant::Wrapper),
+                ("GasCost", SchemaVariant::ChildAndCount(u32::MAX as u64)),
+                ("GasBudget", SchemaVariant::ChildAndCount(u32::MAX as u64)),
+            ],
+            user_schema,
+            wrapper_schema,
+        )
+    }
+}
+
+// Serde tags enums externally: each node is an object with a single key naming its variant.
+pub(crate) fn json_schema(
+    title: &str,
+    variants: &[(&str, SchemaVariant)],
+    user_schema: Value,
+    wrapper_schema: Value,
+) -> Value {
+    let node = json!({ "$ref": "#/$defs/node" });
+    let children = json!({ "type": "array", "items": node });
+    let variants: Vec<Value> = variants
+        .iter()
+        .map(|(name, variant)| {
+            let contents = match variant {
+                SchemaVariant::Children => children.clone(),
+                SchemaVariant::Child => node.clone(),
+                SchemaVariant::ChildAndCount(maximum) => json!({
+                    "type": "array",
+                    "prefixItems": [node, { "type": "integer", "minimum": 0, "maximum": maximum }],
+                    "minItems": 2,
+                    "items": false,
+                }),
+                SchemaVariant::User => json!({ "$ref": "#/$defs/user" }),
+                SchemaVariant::Wrapper => json!({
+                    "type": "array",
+                    "prefixItems": [{ "$ref": "#/$defs/wrapper" }, children],
+                    "minItems": 2,
+                    "items": false,
+                }),
+            };
+            json!({
+                "type": "object",
+                "properties": { *name: contents },
+                "required": [name],
+                "additionalProperties": false,
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": title,
+        "$ref": "#/$defs/node",
+        "$defs": {
+            "node": { "oneOf": variants },
+            "user": user_schema,
+            "wrapper": wrapper_schema,
+        },
+    })
+}
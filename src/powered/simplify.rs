@@ -0,0 +1,249 @@
+use std::fmt;
+
+use super::{BehaviorTreeDef, BehaviorTreePath, UserNodeDefinition, UserWrapperDefinition};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum BehaviorTreeRewrite {
+    // Inverter(Inverter(x)) became x.
+    DoubleInversion,
+    // Inverter(Succeeder(x)) became Failer(x), or Inverter(Failer(x)) became Succeeder(x).
+    InvertedFixedOutcome,
+    // A Succeeder or Failer directly around a Succeeder, Failer or Inverter dropped the inner
+    // one, whose outcome it overrides anyway.
+    OverriddenDecorator,
+    // A Sequence or Selector with a single child became that child.
+    SingleChildComposite,
+    // The children of a Sequence in a Sequence, or a Selector in a Selector, moved up into
+    // the outer one.
+    NestedComposite,
+    // Repeat(x, 1) became x.
+    SingleRepeat,
+    // Repeat(Repeat(x, a), b) became Repeat(x, a * b).
+    NestedRepeat,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BehaviorTreeSimplification {
+    // The path in the original definition of the node that was rewritten.
+    pub path: BehaviorTreePath,
+    pub rewrite: BehaviorTreeRewrite,
+}
+
+impl fmt::Display for BehaviorTreeSimplification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {:?}: ", self.path)?;
+        match self.rewrite {
+            BehaviorTreeRewrite::DoubleInversion => write!(f, "removed a double inversion"),
+            BehaviorTreeRewrite::InvertedFixedOutcome => {
+                write!(
+                    f,
+                    "replaced an inverted Succeeder or Failer with its opposite"
+                )
+            }
+            BehaviorTreeRewrite::OverriddenDecorator => {
+                write!(f, "removed a decorator whose outcome is overridden")
+            }
+            BehaviorTreeRewrite::SingleChildComposite => {
+                write!(f, "replaced a single-child composite with its child")
+            }
+            BehaviorTreeRewrite::NestedComposite => {
+                write!(f, "merged a nested composite of the same kind")
+            }
+            BehaviorTreeRewrite::SingleRepeat => write!(f, "removed a repeat of once"),
+            BehaviorTreeRewrite::NestedRepeat => write!(f, "merged nested repeats"),
+        }
+    }
+}
+
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    // Applies rewrites that leave the tree's behavior unchanged, returning the simplified
+    // definition and what was changed, innermost first. User nodes, wrappers and gas nodes are
+    // kept as they are, and nothing is merged across them.
+    //
+    // The rewrites assume built-in nodes charge no gas, as with BehaviorTreeGasCosts::free(),
+    // since removing a node removes its charges. They also assume the tree is only reset
+    // after it finishes, as the runners do: an explicit reset while waiting only resets the
+    // node it's called on, so removed nodes would have kept different state.
+    // Node paths, and so audits and profiles, change with the shape of the tree.
+    pub fn simplify(self) -> (Self, Vec<BehaviorTreeSimplification>) {
+        let mut simplifications = Vec::new();
+        let simplified = self.simplify_at(&mut Vec::new(), &mut simplifications);
+        (simplified, simplifications)
+    }
+
+    fn simplify_at(
+        self,
+        path: &mut BehaviorTreePath,
+        simplifications: &mut Vec<BehaviorTreeSimplification>,
+    ) -> Self {
+        let mut simplify_child = |index: usize, node_def: Self| {
+            path.push(index);
+            let node_def = node_def.simplify_at(path, simplifications);
+            path.pop();
+            node_def
+        };
+        let mut simplify_children = |node_defs: Vec<Self>| -> Vec<Self> {
+            node_defs
+                .into_iter()
+                .enumerate()
+                .map(|(index, node_def)| simplify_child(index, node_def))
+                .collect()
+        };
+        let mut node_def = match self {
+            BehaviorTreeDef::Sequence(node_defs) => {
+                BehaviorTreeDef::Sequence(simplify_children(node_defs))
+            }
+            BehaviorTreeDef::Selector(node_defs) => {
+                BehaviorTreeDef::Selector(simplify_children(node_defs))
+            }
+            BehaviorTreeDef::Wrapper(wrapper_def, node_defs) => {
+                BehaviorTreeDef::Wrapper(wrapper_def, simplify_children(node_defs))
+            }
+            BehaviorTreeDef::Repeat(node_def, repeats) => {
+                BehaviorTreeDef::Repeat(Box::new(simplify_child(0, *node_def)), repeats)
+            }
+            BehaviorTreeDef::RepeatUntilSuccess(node_def) => {
+                BehaviorTreeDef::RepeatUntilSuccess(Box::new(simplify_child(0, *node_def)))
+            }
+            BehaviorTreeDef::RepeatUntilFail(node_def) => {
+                BehaviorTreeDef::RepeatUntilFail(Box::new(simplify_child(0, *node_def)))
+            }
+            BehaviorTreeDef::Succeeder(node_def) => {
+                BehaviorTreeDef::Succeeder(Box::new(simplify_child(0, *node_def)))
+            }
+            BehaviorTreeDef::Failer(node_def) => {
+                BehaviorTreeDef::Failer(Box::new(simplify_child(0, *node_def)))
+            }
+            BehaviorTreeDef::Inverter(node_def) => {
+                BehaviorTreeDef::Inverter(Box::new(simplify_child(0, *node_def)))
+            }
+            BehaviorTreeDef::GasCost(node_def, gas_cost) => {
+                BehaviorTreeDef::GasCost(Box::new(simplify_child(0, *node_def)), gas_cost)
+            }
+            BehaviorTreeDef::GasBudget(node_def, allowance) => {
+                BehaviorTreeDef::GasBudget(Box::new(simplify_child(0, *node_def)), allowance)
+            }
+            user @ BehaviorTreeDef::User(_) => user,
+        };
+        // The children are already as simple as they get, but a rewrite here can expose
+        // another one here, e.g. Inverter(Inverter(Inverter(x))).
+        loop {
+            match node_def.rewrite() {
+                Ok((rewritten, rewrite)) => {
+                    simplifications.push(BehaviorTreeSimplification {
+                        path: path.clone(),
+                        rewrite,
+                    });
+                    node_def = rewritten;
+                }
+                Err(unchanged) => return unchanged,
+            }
+        }
+    }
+
+    // Applies one rewrite to this node, or gives it back if none applies.
+    fn rewrite(self) -> Result<(Self, BehaviorTreeRewrite), Self> {
+        match self {
+            BehaviorTreeDef::Inverter(node_def) => match *node_def {
+                BehaviorTreeDef::Inverter(inner) => {
+                    Ok((*inner, BehaviorTreeRewrite::DoubleInversion))
+                }
+                BehaviorTreeDef::Succeeder(inner) => Ok((
+                    BehaviorTreeDef::Failer(inner),
+                    BehaviorTreeRewrite::InvertedFixedOutcome,
+                )),
+                BehaviorTreeDef::Failer(inner) => Ok((
+                    BehaviorTreeDef::Succeeder(inner),
+                    BehaviorTreeRewrite::InvertedFixedOutcome,
+                )),
+                node_def => Err(BehaviorTreeDef::Inverter(Box::new(node_def))),
+            },
+            BehaviorTreeDef::Succeeder(node_def) => match *node_def {
+                BehaviorTreeDef::Succeeder(inner)
+                | BehaviorTreeDef::Failer(inner)
+                | BehaviorTreeDef::Inverter(inner) => Ok((
+                    BehaviorTreeDef::Succeeder(inner),
+                    BehaviorTreeRewrite::OverriddenDecorator,
+                )),
+                node_def => Err(BehaviorTreeDef::Succeeder(Box::new(node_def))),
+            },
+            BehaviorTreeDef::Failer(node_def) => match *node_def {
+                BehaviorTreeDef::Succeeder(inner)
+                | BehaviorTreeDef::Failer(inner)
+                | BehaviorTreeDef::Inverter(inner) => Ok((
+                    BehaviorTreeDef::Failer(inner),
+                    BehaviorTreeRewrite::OverriddenDecorator,
+                )),
+                node_def => Err(BehaviorTreeDef::Failer(Box::new(node_def))),
+            },
+            BehaviorTreeDef::Sequence(mut node_defs) | BehaviorTreeDef::Selector(mut node_defs)
+                if node_defs.len() == 1 =>
+            {
+                Ok((
+                    node_defs.pop().unwrap(),
+                    BehaviorTreeRewrite::SingleChildComposite,
+                ))
+            }
+            BehaviorTreeDef::Sequence(node_defs)
+                if node_defs
+                    .iter()
+                    .any(|node_def| matches!(node_def, BehaviorTreeDef::Sequence(_))) =>
+            {
+                let node_defs = flatten(node_defs, |node_def| match node_def {
+                    BehaviorTreeDef::Sequence(inner) => Ok(inner),
+                    node_def => Err(node_def),
+                });
+                Ok((
+                    BehaviorTreeDef::Sequence(node_defs),
+                    BehaviorTreeRewrite::NestedComposite,
+                ))
+            }
+            BehaviorTreeDef::Selector(node_defs)
+                if node_defs
+                    .iter()
+                    .any(|node_def| matches!(node_def, BehaviorTreeDef::Selector(_))) =>
+            {
+                let node_defs = flatten(node_defs, |node_def| match node_def {
+                    BehaviorTreeDef::Selector(inner) => Ok(inner),
+                    node_def => Err(node_def),
+                });
+                Ok((
+                    BehaviorTreeDef::Selector(node_defs),
+                    BehaviorTreeRewrite::NestedComposite,
+                ))
+            }
+            BehaviorTreeDef::Repeat(node_def, 1) => {
+                Ok((*node_def, BehaviorTreeRewrite::SingleRepeat))
+            }
+            BehaviorTreeDef::Repeat(node_def, outer_repeats) => match *node_def {
+                // Both only move on when the innermost node completes, and start over when it
+                // fails, so the counts multiply.
+                BehaviorTreeDef::Repeat(inner, inner_repeats)
+                    if inner_repeats.checked_mul(outer_repeats).is_some() =>
+                {
+                    Ok((
+                        BehaviorTreeDef::Repeat(inner, inner_repeats * outer_repeats),
+                        BehaviorTreeRewrite::NestedRepeat,
+                    ))
+                }
+                node_def => Err(BehaviorTreeDef::Repeat(Box::new(node_def), outer_repeats)),
+            },
+            node_def => Err(node_def),
+        }
+    }
+}
+
+// Replaces each node the split accepts with the children it gives back.
+fn flatten<U: UserNodeDefinition, W: UserWrapperDefinition<U>>(
+    node_defs: Vec<BehaviorTreeDef<U, W>>,
+    split: impl Fn(BehaviorTreeDef<U, W>) -> Result<Vec<BehaviorTreeDef<U, W>>, BehaviorTreeDef<U, W>>,
+) -> Vec<BehaviorTreeDef<U, W>> {
+    let mut flattened = Vec::new();
+    for node_def in node_defs {
+        match split(node_def) {
+            Ok(inner) => flattened.extend(inner),
+            Err(node_def) => flattened.push(node_def),
+        }
+    }
+    flattened
+}
@@ -1,10 +1,14 @@
+use std::fmt;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use super::{nodes::*, BehaviorTree};
+use super::{
+    nodes::*, Audited, BehaviorTree, BehaviorTreePath, BehaviorTreeProfiler, Gas, Profiled,
+};
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum BehaviorTreeDef<U: UserNodeDefinition, W: UserWrapperDefinition<U>> {
     Sequence(Vec<BehaviorTreeDef<U, W>>),
     Selector(Vec<BehaviorTreeDef<U, W>>),
@@ -16,14 +20,139 @@ pub enum BehaviorTreeDef<U: UserNodeDefinition, W: UserWrapperDefinition<U>> {
     Inverter(Box<BehaviorTreeDef<U, W>>),
     User(U),
     Wrapper(W, Vec<BehaviorTreeDef<U, W>>),
+    // Charges the given gas each time the inner node is entered or resumed.
+    //
+    // This is a node of its own, so it adds a level to the tree: the inner node and
+    // everything under it sit one index deeper than without it, and audits, coverage,
+    // replays, DOT overlays, profiles and reloads all see the extra node. Adding or removing
+    // one shifts those paths. To charge built-in nodes without changing the tree's shape,
+    // pass BehaviorTreeGasCosts to create_tree_with_gas_costs instead.
+    GasCost(Box<BehaviorTreeDef<U, W>>, u32),
+    // Caps the gas the inner node can spend each time it is resumed.
+    GasBudget(Box<BehaviorTreeDef<U, W>>, u32),
+}
+
+// The gas each kind of built-in node charges each time it enters or resumes a child. Nodes
+// that can't afford it wait for gas, and charge again when they are next resumed.
+//
+// By default, which create_tree uses, every kind charges 1, so a deep tree of composites
+// costs gas like its leaves do. free() charges nothing, leaving gas to user leaves and
+// GasCost nodes. Unlike GasCost nodes, these costs leave the tree's shape and paths alone.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct BehaviorTreeGasCosts {
+    pub sequence: u32,
+    pub selector: u32,
+    pub repeat: u32,
+    pub repeat_until_success: u32,
+    pub repeat_until_fail: u32,
+    pub succeeder: u32,
+    pub failer: u32,
+    pub inverter: u32,
+}
+
+impl Default for BehaviorTreeGasCosts {
+    fn default() -> Self {
+        Self::uniform(1)
+    }
+}
+
+impl BehaviorTreeGasCosts {
+    // Every built-in node kind charges the same cost.
+    pub fn uniform(gas_cost: u32) -> Self {
+        BehaviorTreeGasCosts {
+            sequence: gas_cost,
+            selector: gas_cost,
+            repeat: gas_cost,
+            repeat_until_success: gas_cost,
+            repeat_until_fail: gas_cost,
+            succeeder: gas_cost,
+            failer: gas_cost,
+            inverter: gas_cost,
+        }
+    }
+
+    // Built-in nodes charge nothing.
+    pub fn free() -> Self {
+        Self::uniform(0)
+    }
+
+    // The cost the node created from the definition charges per child it enters or resumes.
+    pub fn of<U: UserNodeDefinition, W: UserWrapperDefinition<U>>(
+        &self,
+        node_def: &BehaviorTreeDef<U, W>,
+    ) -> u32 {
+        match node_def {
+            BehaviorTreeDef::Sequence(_) => self.sequence,
+            BehaviorTreeDef::Selector(_) => self.selector,
+            BehaviorTreeDef::Repeat(_, _) => self.repeat,
+            BehaviorTreeDef::RepeatUntilSuccess(_) => self.repeat_until_success,
+            BehaviorTreeDef::RepeatUntilFail(_) => self.repeat_until_fail,
+            BehaviorTreeDef::Succeeder(_) => self.succeeder,
+            BehaviorTreeDef::Failer(_) => self.failer,
+            BehaviorTreeDef::Inverter(_) => self.inverter,
+            BehaviorTreeDef::GasCost(_, gas_cost) => *gas_cost,
+            BehaviorTreeDef::User(_)
+            | BehaviorTreeDef::Wrapper(_, _)
+            | BehaviorTreeDef::GasBudget(_, _) => 0,
+        }
+    }
+}
+
+// Why a user definition couldn't create its node.
+pub type BehaviorTreeNodeError = Box<dyn std::error::Error + Send + Sync>;
+
+// A user or wrapper definition that failed to create its node, and where it is in the tree.
+#[derive(Debug)]
+pub struct BehaviorTreeCreateError {
+    pub path: BehaviorTreePath,
+    // The kind of the failing definition, "User" or "Wrapper".
+    pub kind: &'static str,
+    // The failing definition, as its describe method renders it.
+    pub node: String,
+    pub source: BehaviorTreeNodeError,
+}
+
+impl fmt::Display for BehaviorTreeCreateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "could not create {} {} at {:?}: {}",
+            self.kind, self.node, self.path, self.source
+        )
+    }
+}
+
+impl std::error::Error for BehaviorTreeCreateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
 }
 
+// The tree created from definitions with user nodes U.
+pub type BoxedBehaviorTreeFor<U> = BoxedBehaviorTree<
+    <U as UserNodeDefinition>::Gas,
+    <U as UserNodeDefinition>::Model,
+    <U as UserNodeDefinition>::Controller,
+>;
+
 pub trait UserNodeDefinition {
     type Model: 'static;
     type Controller: 'static;
-    fn create_node(
-        &self,
-    ) -> Box<dyn BehaviorTree<Model = Self::Model, Controller = Self::Controller> + Send + Sync>;
+    type Gas: Gas + 'static;
+    fn create_node(&self) -> BoxedBehaviorTreeFor<Self>;
+
+    // Creates the node, or says why it can't. Defaults to create_node, which can't fail.
+    fn try_create_node(&self) -> Result<BoxedBehaviorTreeFor<Self>, BehaviorTreeNodeError> {
+        Ok(self.create_node())
+    }
+
+    // How errors show this definition. Defaults to the name of its type; definitions
+    // that are Debug can return format!("{:?}", self) instead.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
 }
 
 impl<M: 'static, C: 'static, D: 'static> UserNodeDefinition for D
@@ -32,86 +161,277 @@ where
 {
     type Model = M;
     type Controller = C;
+    type Gas = Option<i32>;
 
-    fn create_node(
-        &self,
-    ) -> Box<dyn BehaviorTree<Model = Self::Model, Controller = Self::Controller> + Send + Sync>
-    {
+    fn create_node(&self) -> BoxedBehaviorTreeFor<Self> {
         Box::new(self.clone())
     }
 }
 
+// The node a wrapper creates should give out the nodes it wraps from children and
+// children_mut, in order, so indexes, coverage, replays and reloading can find them. Wrappers
+// that hide them are opaque: they are seen as a whole, and reloaded as a whole when any of
+// the definitions under them changes.
 pub trait UserWrapperDefinition<U: UserNodeDefinition> {
-    fn create_node_and_wrap(
+    fn create_node_and_wrap(&self, nodes: Vec<BoxedBehaviorTreeFor<U>>) -> BoxedBehaviorTreeFor<U>;
+
+    // Creates the node, or says why it can't. Defaults to create_node_and_wrap, which can't fail.
+    fn try_create_node_and_wrap(
         &self,
-        nodes: Vec<
-            Box<dyn BehaviorTree<Model = U::Model, Controller = U::Controller> + Send + Sync>,
-        >,
-    ) -> Box<dyn BehaviorTree<Model = U::Model, Controller = U::Controller> + Send + Sync>;
+        nodes: Vec<BoxedBehaviorTreeFor<U>>,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeNodeError> {
+        Ok(self.create_node_and_wrap(nodes))
+    }
+
+    // How errors show this definition, like UserNodeDefinition::describe.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+
+    // Whether this only stands in for a real wrapper and can't create nodes, like ().
+    fn is_placeholder(&self) -> bool {
+        false
+    }
 }
 
 impl<U: UserNodeDefinition> UserWrapperDefinition<U> for () {
     fn create_node_and_wrap(
         &self,
-        _nodes: Vec<
-            Box<dyn BehaviorTree<Model = U::Model, Controller = U::Controller> + Send + Sync>,
-        >,
-    ) -> Box<dyn BehaviorTree<Model = U::Model, Controller = U::Controller> + Send + Sync> {
+        _nodes: Vec<BoxedBehaviorTreeFor<U>>,
+    ) -> BoxedBehaviorTreeFor<U> {
         panic!("Cannot create a wrapper with no definition");
     }
+
+    fn try_create_node_and_wrap(
+        &self,
+        _nodes: Vec<BoxedBehaviorTreeFor<U>>,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeNodeError> {
+        Err("Cannot create a wrapper with no definition".into())
+    }
+
+    fn is_placeholder(&self) -> bool {
+        true
+    }
 }
 
 impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
-    pub fn create_tree(
-        &self,
-    ) -> Box<dyn BehaviorTree<Model = U::Model, Controller = U::Controller> + Send + Sync> {
+    // The name of the variant, e.g. "Sequence" or "User".
+    pub fn kind_name(&self) -> &'static str {
         match self {
-            BehaviorTreeDef::Sequence(node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                Box::new(Sequence::new(nodes))
-            }
-            BehaviorTreeDef::Selector(node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                Box::new(Selector::new(nodes))
+            BehaviorTreeDef::Sequence(_) => "Sequence",
+            BehaviorTreeDef::Selector(_) => "Selector",
+            BehaviorTreeDef::Repeat(_, _) => "Repeat",
+            BehaviorTreeDef::RepeatUntilSuccess(_) => "RepeatUntilSuccess",
+            BehaviorTreeDef::RepeatUntilFail(_) => "RepeatUntilFail",
+            BehaviorTreeDef::Succeeder(_) => "Succeeder",
+            BehaviorTreeDef::Failer(_) => "Failer",
+            BehaviorTreeDef::Inverter(_) => "Inverter",
+            BehaviorTreeDef::User(_) => "User",
+            BehaviorTreeDef::Wrapper(_, _) => "Wrapper",
+            BehaviorTreeDef::GasCost(_, _) => "GasCost",
+            BehaviorTreeDef::GasBudget(_, _) => "GasBudget",
+        }
+    }
+
+    // The direct child definitions, in the same order as the created node's children.
+    pub fn children(&self) -> &[BehaviorTreeDef<U, W>] {
+        match self {
+            BehaviorTreeDef::Sequence(node_defs)
+            | BehaviorTreeDef::Selector(node_defs)
+            | BehaviorTreeDef::Wrapper(_, node_defs) => node_defs,
+            BehaviorTreeDef::Repeat(node_def, _)
+            | BehaviorTreeDef::RepeatUntilSuccess(node_def)
+            | BehaviorTreeDef::RepeatUntilFail(node_def)
+            | BehaviorTreeDef::Succeeder(node_def)
+            | BehaviorTreeDef::Failer(node_def)
+            | BehaviorTreeDef::Inverter(node_def)
+            | BehaviorTreeDef::GasCost(node_def, _)
+            | BehaviorTreeDef::GasBudget(node_def, _) => std::slice::from_ref(node_def.as_ref()),
+            BehaviorTreeDef::User(_) => &[],
+        }
+    }
+
+    // The definition at the given path of child indices, if there is one.
+    pub fn get(&self, path: &[usize]) -> Option<&BehaviorTreeDef<U, W>> {
+        match path.split_first() {
+            Some((index, rest)) => self.children().get(*index)?.get(rest),
+            None => Some(self),
+        }
+    }
+
+    pub fn children_mut(&mut self) -> &mut [BehaviorTreeDef<U, W>] {
+        match self {
+            BehaviorTreeDef::Sequence(node_defs)
+            | BehaviorTreeDef::Selector(node_defs)
+            | BehaviorTreeDef::Wrapper(_, node_defs) => node_defs,
+            BehaviorTreeDef::Repeat(node_def, _)
+            | BehaviorTreeDef::RepeatUntilSuccess(node_def)
+            | BehaviorTreeDef::RepeatUntilFail(node_def)
+            | BehaviorTreeDef::Succeeder(node_def)
+            | BehaviorTreeDef::Failer(node_def)
+            | BehaviorTreeDef::Inverter(node_def)
+            | BehaviorTreeDef::GasCost(node_def, _)
+            | BehaviorTreeDef::GasBudget(node_def, _) => std::slice::from_mut(node_def.as_mut()),
+            BehaviorTreeDef::User(_) => &mut [],
+        }
+    }
+
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut BehaviorTreeDef<U, W>> {
+        match path.split_first() {
+            Some((index, rest)) => self.children_mut().get_mut(*index)?.get_mut(rest),
+            None => Some(self),
+        }
+    }
+
+    // Creates the tree, panicking if a user or wrapper definition can't create its node.
+    // See try_create_tree. Built-in nodes charge the default BehaviorTreeGasCosts.
+    pub fn create_tree(&self) -> BoxedBehaviorTreeFor<U> {
+        self.try_create_tree()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Creates the tree with built-in nodes charging gas according to their kind, e.g.
+    // BehaviorTreeGasCosts::free() for none.
+    pub fn create_tree_with_gas_costs(
+        &self,
+        gas_costs: &BehaviorTreeGasCosts,
+    ) -> BoxedBehaviorTreeFor<U> {
+        self.try_create_tree_with_gas_costs(gas_costs)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Creates the tree with every node wrapped in a Profiled node reporting to the profiler.
+    pub fn create_profiled_tree(&self, profiler: &BehaviorTreeProfiler) -> BoxedBehaviorTreeFor<U> {
+        self.create_profiled_tree_with_gas_costs(profiler, &BehaviorTreeGasCosts::default())
+    }
+
+    pub fn create_profiled_tree_with_gas_costs(
+        &self,
+        profiler: &BehaviorTreeProfiler,
+        gas_costs: &BehaviorTreeGasCosts,
+    ) -> BoxedBehaviorTreeFor<U> {
+        self.try_create_profiled_tree_with_gas_costs(profiler, gas_costs)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Creates the tree, or reports the first user or wrapper definition that couldn't
+    // create its node.
+    pub fn try_create_tree(&self) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeCreateError> {
+        self.try_create_tree_with_gas_costs(&BehaviorTreeGasCosts::default())
+    }
+
+    pub fn try_create_tree_with_gas_costs(
+        &self,
+        gas_costs: &BehaviorTreeGasCosts,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeCreateError> {
+        self.try_create_tree_decorated(gas_costs, &|_, node| node, &mut Vec::new())
+    }
+
+    pub fn try_create_profiled_tree(
+        &self,
+        profiler: &BehaviorTreeProfiler,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeCreateError> {
+        self.try_create_profiled_tree_with_gas_costs(profiler, &BehaviorTreeGasCosts::default())
+    }
+
+    pub fn try_create_profiled_tree_with_gas_costs(
+        &self,
+        profiler: &BehaviorTreeProfiler,
+        gas_costs: &BehaviorTreeGasCosts,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeCreateError> {
+        self.try_create_tree_decorated(
+            gas_costs,
+            &|_, node| Box::new(Profiled::new(node, profiler.clone())),
+            &mut Vec::new(),
+        )
+    }
+
+    // Creates the tree with every user node and wrapper wrapped in an Audited node, so that
+    // coverage sees their entries and outcomes even if they don't audit themselves.
+    pub fn create_audited_tree(&self) -> BoxedBehaviorTreeFor<U> {
+        self.try_create_audited_tree()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_create_audited_tree(
+        &self,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeCreateError> {
+        self.try_create_tree_decorated(
+            &BehaviorTreeGasCosts::default(),
+            &audit_user_nodes,
+            &mut Vec::new(),
+        )
+    }
+
+    pub(crate) fn try_create_tree_decorated(
+        &self,
+        gas_costs: &BehaviorTreeGasCosts,
+        decorate: &dyn Fn(&Self, BoxedBehaviorTreeFor<U>) -> BoxedBehaviorTreeFor<U>,
+        path: &mut BehaviorTreePath,
+    ) -> Result<BoxedBehaviorTreeFor<U>, BehaviorTreeCreateError> {
+        let mut children = Vec::new();
+        for (index, node_def) in self.children().iter().enumerate() {
+            path.push(index);
+            children.push(node_def.try_create_tree_decorated(gas_costs, decorate, path)?);
+            path.pop();
+        }
+        let failed = |source| BehaviorTreeCreateError {
+            path: path.clone(),
+            kind: self.kind_name(),
+            node: match self {
+                BehaviorTreeDef::User(node_def) => node_def.describe(),
+                BehaviorTreeDef::Wrapper(wrapper_def, _) => wrapper_def.describe(),
+                _ => String::new(),
+            },
+            source,
+        };
+        let mut only_child = || children.pop().unwrap();
+        let node: BoxedBehaviorTreeFor<U> = match self {
+            BehaviorTreeDef::Sequence(_) => {
+                Box::new(Sequence::new(children).with_gas_cost(gas_costs.sequence))
             }
-            BehaviorTreeDef::Repeat(node_def, repeats) => {
-                let node = node_def.create_tree();
-                Box::new(Repeat::new(node, *repeats))
+            BehaviorTreeDef::Selector(_) => {
+                Box::new(Selector::new(children).with_gas_cost(gas_costs.selector))
             }
-            BehaviorTreeDef::RepeatUntilFail(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(RepeatUntilFail::new(node))
+            BehaviorTreeDef::Repeat(_, repeats) => {
+                Box::new(Repeat::new(only_child(), *repeats).with_gas_cost(gas_costs.repeat))
             }
-            BehaviorTreeDef::RepeatUntilSuccess(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(RepeatUntilSuccess::new(node))
+            BehaviorTreeDef::RepeatUntilFail(_) => Box::new(
+                RepeatUntilFail::new(only_child()).with_gas_cost(gas_costs.repeat_until_fail),
+            ),
+            BehaviorTreeDef::RepeatUntilSuccess(_) => Box::new(
+                RepeatUntilSuccess::new(only_child()).with_gas_cost(gas_costs.repeat_until_success),
+            ),
+            BehaviorTreeDef::Succeeder(_) => {
+                Box::new(Succeeder::new(only_child()).with_gas_cost(gas_costs.succeeder))
             }
-            BehaviorTreeDef::Succeeder(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(Succeeder::new(node))
+            BehaviorTreeDef::Inverter(_) => {
+                Box::new(Inverter::new(only_child()).with_gas_cost(gas_costs.inverter))
             }
-            BehaviorTreeDef::Inverter(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(Inverter::new(node))
+            BehaviorTreeDef::Failer(_) => {
+                Box::new(Failer::new(only_child()).with_gas_cost(gas_costs.failer))
             }
-            BehaviorTreeDef::Failer(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(Failer::new(node))
+            BehaviorTreeDef::User(node_def) => node_def.try_create_node().map_err(failed)?,
+            BehaviorTreeDef::Wrapper(wrapper_def, _) => wrapper_def
+                .try_create_node_and_wrap(children)
+                .map_err(failed)?,
+            BehaviorTreeDef::GasCost(_, gas_cost) => {
+                Box::new(GasCost::new(only_child(), *gas_cost))
             }
-            BehaviorTreeDef::User(node_def) => node_def.create_node(),
-            BehaviorTreeDef::Wrapper(wrapper_def, node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                wrapper_def.create_node_and_wrap(nodes)
+            BehaviorTreeDef::GasBudget(_, allowance) => {
+                Box::new(GasBudget::new(only_child(), *allowance))
             }
-        }
+        };
+        Ok(decorate(self, node))
+    }
+}
+
+pub(crate) fn audit_user_nodes<U: UserNodeDefinition, W: UserWrapperDefinition<U>>(
+    node_def: &BehaviorTreeDef<U, W>,
+    node: BoxedBehaviorTreeFor<U>,
+) -> BoxedBehaviorTreeFor<U> {
+    match node_def {
+        BehaviorTreeDef::User(_) | BehaviorTreeDef::Wrapper(_, _) => Box::new(Audited::new(node)),
+        _ => node,
     }
 }
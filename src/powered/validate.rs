@@ -0,0 +1,158 @@
+use std::fmt;
+
+use super::{BehaviorTreeDef, BehaviorTreePath, UserNodeDefinition, UserWrapperDefinition};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum BehaviorTreeProblem {
+    // A Sequence, Selector or Executor with no children, which finishes without doing anything.
+    EmptyComposite,
+    // A Repeat that runs its child zero times.
+    ZeroRepeats,
+    // A Wrapper whose definition can't create nodes, so creating the tree panics.
+    PlaceholderWrapper,
+    // A child that never runs, because the earlier child at this index always ends its
+    // Selector (by completing) or its Sequence (by failing).
+    Unreachable { cut_off_by: usize },
+    // A RepeatUntilSuccess around a child that always fails, or a RepeatUntilFail around a
+    // child that always completes, which loops forever within a single resume.
+    EndlessRepeat,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BehaviorTreeDiagnostic {
+    // The path of child indices to the offending definition.
+    pub path: BehaviorTreePath,
+    // The kind of the offending definition, e.g. "Selector".
+    pub kind: &'static str,
+    pub problem: BehaviorTreeProblem,
+}
+
+impl fmt::Display for BehaviorTreeDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {:?}: ", self.kind, self.path)?;
+        match &self.problem {
+            BehaviorTreeProblem::EmptyComposite => write!(f, "has no children"),
+            BehaviorTreeProblem::ZeroRepeats => write!(f, "repeats zero times"),
+            BehaviorTreeProblem::PlaceholderWrapper => {
+                write!(f, "has a placeholder wrapper, which panics when created")
+            }
+            BehaviorTreeProblem::Unreachable { cut_off_by } => {
+                write!(
+                    f,
+                    "is unreachable, as sibling {} always ends its parent",
+                    cut_off_by
+                )
+            }
+            BehaviorTreeProblem::EndlessRepeat => write!(f, "repeats forever"),
+        }
+    }
+}
+
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    // Finds definitions that are certainly wrong: ones that panic when created, loop
+    // forever, or can never run. User nodes are assumed to be able to both complete and fail.
+    // Diagnostics are in path order.
+    pub fn validate(&self) -> Vec<BehaviorTreeDiagnostic> {
+        let mut diagnostics = Vec::new();
+        self.validate_at(&mut Vec::new(), &mut diagnostics);
+        diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+        diagnostics
+    }
+
+    fn validate_at(
+        &self,
+        path: &mut BehaviorTreePath,
+        diagnostics: &mut Vec<BehaviorTreeDiagnostic>,
+    ) {
+        let mut report = |path: &BehaviorTreePath, kind, problem| {
+            diagnostics.push(BehaviorTreeDiagnostic {
+                path: path.clone(),
+                kind,
+                problem,
+            })
+        };
+        match self {
+            BehaviorTreeDef::Sequence(node_defs) | BehaviorTreeDef::Selector(node_defs) => {
+                if node_defs.is_empty() {
+                    report(path, self.kind_name(), BehaviorTreeProblem::EmptyComposite);
+                }
+                // A Sequence is cut short by a failure, a Selector by a completion.
+                let ending = matches!(self, BehaviorTreeDef::Selector(_));
+                let cut_off_by = node_defs
+                    .iter()
+                    .position(|node_def| node_def.fixed_outcome() == Some(ending));
+                if let Some(cut_off_by) = cut_off_by {
+                    for (index, node_def) in node_defs.iter().enumerate().skip(cut_off_by + 1) {
+                        path.push(index);
+                        report(
+                            path,
+                            node_def.kind_name(),
+                            BehaviorTreeProblem::Unreachable { cut_off_by },
+                        );
+                        path.pop();
+                    }
+                }
+            }
+            BehaviorTreeDef::Repeat(_, 0) => {
+                report(path, self.kind_name(), BehaviorTreeProblem::ZeroRepeats)
+            }
+            BehaviorTreeDef::RepeatUntilSuccess(node_def)
+                if node_def.fixed_outcome() == Some(false) =>
+            {
+                report(path, self.kind_name(), BehaviorTreeProblem::EndlessRepeat)
+            }
+            BehaviorTreeDef::RepeatUntilFail(node_def)
+                if node_def.fixed_outcome() == Some(true) =>
+            {
+                report(path, self.kind_name(), BehaviorTreeProblem::EndlessRepeat)
+            }
+            BehaviorTreeDef::Wrapper(wrapper_def, _) if wrapper_def.is_placeholder() => report(
+                path,
+                self.kind_name(),
+                BehaviorTreeProblem::PlaceholderWrapper,
+            ),
+            _ => {}
+        }
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            child.validate_at(path, diagnostics);
+            path.pop();
+        }
+    }
+
+    // Some(true) if the node always completes when it finishes, Some(false) if it always
+    // fails, and None if it depends on user nodes.
+    fn fixed_outcome(&self) -> Option<bool> {
+        match self {
+            BehaviorTreeDef::Sequence(node_defs) => first_decisive(node_defs, false),
+            BehaviorTreeDef::Selector(node_defs) => first_decisive(node_defs, true),
+            BehaviorTreeDef::Repeat(_, 0) => Some(true),
+            BehaviorTreeDef::Repeat(node_def, _)
+            | BehaviorTreeDef::GasCost(node_def, _)
+            | BehaviorTreeDef::GasBudget(node_def, _) => node_def.fixed_outcome(),
+            BehaviorTreeDef::RepeatUntilSuccess(_)
+            | BehaviorTreeDef::RepeatUntilFail(_)
+            | BehaviorTreeDef::Succeeder(_) => Some(true),
+            BehaviorTreeDef::Failer(_) => Some(false),
+            BehaviorTreeDef::Inverter(node_def) => node_def.fixed_outcome().map(|outcome| !outcome),
+            BehaviorTreeDef::User(_) | BehaviorTreeDef::Wrapper(_, _) => None,
+        }
+    }
+}
+
+// The outcome of a Sequence (decisive = false) or Selector (decisive = true): the first
+// child with the decisive outcome ends it with that outcome, and if every child has the
+// other outcome, so does the composite. Anything uncertain before that leaves it open.
+fn first_decisive<U: UserNodeDefinition, W: UserWrapperDefinition<U>>(
+    node_defs: &[BehaviorTreeDef<U, W>],
+    decisive: bool,
+) -> Option<bool> {
+    for node_def in node_defs {
+        match node_def.fixed_outcome() {
+            Some(outcome) if outcome == decisive => return Some(decisive),
+            Some(_) => {}
+            None => return None,
+        }
+    }
+    Some(!decisive)
+}
@@ -0,0 +1,109 @@
+use super::{BehaviorTreeDef, BehaviorTreePath, UserNodeDefinition, UserWrapperDefinition};
+
+impl<U: UserNodeDefinition, W: UserWrapperDefinition<U>> BehaviorTreeDef<U, W> {
+    // Calls the visitor with every definition and its path, parents before children.
+    pub fn visit(&self, visitor: &mut impl FnMut(&BehaviorTreePath, &BehaviorTreeDef<U, W>)) {
+        self.visit_at(&mut Vec::new(), visitor);
+    }
+
+    fn visit_at(
+        &self,
+        path: &mut BehaviorTreePath,
+        visitor: &mut impl FnMut(&BehaviorTreePath, &BehaviorTreeDef<U, W>),
+    ) {
+        visitor(path, self);
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            child.visit_at(path, visitor);
+            path.pop();
+        }
+    }
+
+    // Like visit, but the visitor can change definitions or replace them outright. A parent
+    // is visited before its children, so the children of whatever it's left as are visited.
+    pub fn visit_mut(
+        &mut self,
+        visitor: &mut impl FnMut(&BehaviorTreePath, &mut BehaviorTreeDef<U, W>),
+    ) {
+        self.visit_mut_at(&mut Vec::new(), visitor);
+    }
+
+    fn visit_mut_at(
+        &mut self,
+        path: &mut BehaviorTreePath,
+        visitor: &mut impl FnMut(&BehaviorTreePath, &mut BehaviorTreeDef<U, W>),
+    ) {
+        visitor(path, self);
+        for (index, child) in self.children_mut().iter_mut().enumerate() {
+            path.push(index);
+            child.visit_mut_at(path, visitor);
+            path.pop();
+        }
+    }
+
+    // Combines the definitions bottom up: the folder gets each definition and its path along
+    // with the results for its children, in order, and the result for the root is returned.
+    pub fn fold<T>(
+        &self,
+        folder: &mut impl FnMut(&BehaviorTreePath, &BehaviorTreeDef<U, W>, Vec<T>) -> T,
+    ) -> T {
+        self.fold_at(&mut Vec::new(), folder)
+    }
+
+    fn fold_at<T>(
+        &self,
+        path: &mut BehaviorTreePath,
+        folder: &mut impl FnMut(&BehaviorTreePath, &BehaviorTreeDef<U, W>, Vec<T>) -> T,
+    ) -> T {
+        let mut children = Vec::new();
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            children.push(child.fold_at(path, folder));
+            path.pop();
+        }
+        folder(path, self, children)
+    }
+
+    // Converts the user and wrapper definitions, keeping everything else.
+    pub fn map_user<U2, W2>(
+        &self,
+        map_user: &impl Fn(&U) -> U2,
+        map_wrapper: &impl Fn(&W) -> W2,
+    ) -> BehaviorTreeDef<U2, W2>
+    where
+        U2: UserNodeDefinition,
+        W2: UserWrapperDefinition<U2>,
+    {
+        self.fold(
+            &mut |_, node_def, mut children: Vec<BehaviorTreeDef<U2, W2>>| {
+                let mut child = || Box::new(children.pop().unwrap());
+                match node_def {
+                    BehaviorTreeDef::Repeat(_, repeats) => {
+                        BehaviorTreeDef::Repeat(child(), *repeats)
+                    }
+                    BehaviorTreeDef::RepeatUntilSuccess(_) => {
+                        BehaviorTreeDef::RepeatUntilSuccess(child())
+                    }
+                    BehaviorTreeDef::RepeatUntilFail(_) => {
+                        BehaviorTreeDef::RepeatUntilFail(child())
+                    }
+                    BehaviorTreeDef::Succeeder(_) => BehaviorTreeDef::Succeeder(child()),
+                    BehaviorTreeDef::Failer(_) => BehaviorTreeDef::Failer(child()),
+                    BehaviorTreeDef::Inverter(_) => BehaviorTreeDef::Inverter(child()),
+                    BehaviorTreeDef::GasCost(_, gas_cost) => {
+                        BehaviorTreeDef::GasCost(child(), *gas_cost)
+                    }
+                    BehaviorTreeDef::GasBudget(_, allowance) => {
+                        BehaviorTreeDef::GasBudget(child(), *allowance)
+                    }
+                    BehaviorTreeDef::User(node_def) => BehaviorTreeDef::User(map_user(node_def)),
+                    BehaviorTreeDef::Sequence(_) => BehaviorTreeDef::Sequence(children),
+                    BehaviorTreeDef::Selector(_) => BehaviorTreeDef::Selector(children),
+                    BehaviorTreeDef::Wrapper(wrapper_def, _) => {
+                        BehaviorTreeDef::Wrapper(map_wrapper(wrapper_def), children)
+                    }
+                }
+            },
+        )
+    }
+}
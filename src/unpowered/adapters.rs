@@ -0,0 +1,51 @@
+use super::{UnpoweredFunction, UnpoweredFunctionState};
+use crate::powered::{BehaviorTreeAudit, BehaviorTreeState, BoxedBehaviorTree, Gas};
+
+// Runs a powered tree inside an unpowered one. Each resume starts from a fresh copy of the
+// gas given here, and running out of gas counts as waiting.
+pub struct PoweredNode<M, C, G = Option<i32>> {
+    tree: BoxedBehaviorTree<G, M, C>,
+    gas: G,
+}
+
+impl<M, C, G> PoweredNode<M, C, G> {
+    pub fn new(tree: BoxedBehaviorTree<G, M, C>, gas: G) -> Self {
+        PoweredNode { tree, gas }
+    }
+}
+
+impl<M: 'static, C: 'static, G: Gas + Clone> UnpoweredFunction for PoweredNode<M, C, G> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        self.resume_with_audit(model, controller, &mut None)
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        let mut gas = self.gas.clone();
+        match self.tree.resume_with(model, controller, &mut gas, audit) {
+            BehaviorTreeState::Complete => UnpoweredFunctionState::Complete,
+            BehaviorTreeState::Failed => UnpoweredFunctionState::Failed,
+            BehaviorTreeState::Waiting | BehaviorTreeState::WaitingForGas => {
+                UnpoweredFunctionState::Waiting
+            }
+        }
+    }
+
+    fn reset(self: &mut Self, model: &Self::Model) {
+        self.tree.reset(model);
+    }
+
+    fn get_name(self: &Self) -> &String {
+        self.tree.get_name()
+    }
+}
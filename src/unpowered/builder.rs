@@ -0,0 +1,52 @@
+use super::{UnpoweredTreeDef, UserNodeDefinition, UserWrapperDefinition};
+
+// The same shorthand as powered::BehaviorTreeDef's builder. See also the unpowered_bt! macro.
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: UserNodeDefinition + Send + Sync,
+    W: UserWrapperDefinition<U> + Send + Sync,
+{
+    pub fn user(node_def: U) -> Self {
+        UnpoweredTreeDef::User(node_def)
+    }
+
+    pub fn sequence(node_defs: impl IntoIterator<Item = Self>) -> Self {
+        UnpoweredTreeDef::Sequence(node_defs.into_iter().collect())
+    }
+
+    pub fn selector(node_defs: impl IntoIterator<Item = Self>) -> Self {
+        UnpoweredTreeDef::Selector(node_defs.into_iter().collect())
+    }
+
+    pub fn executor(node_defs: impl IntoIterator<Item = Self>) -> Self {
+        UnpoweredTreeDef::Executor(node_defs.into_iter().collect())
+    }
+
+    pub fn wrapper(wrapper_def: W, node_defs: impl IntoIterator<Item = Self>) -> Self {
+        UnpoweredTreeDef::Wrapper(wrapper_def, node_defs.into_iter().collect())
+    }
+
+    pub fn repeat(self, repeats: usize) -> Self {
+        UnpoweredTreeDef::Repeat(Box::new(self), repeats)
+    }
+
+    pub fn repeat_until_success(self) -> Self {
+        UnpoweredTreeDef::RepeatUntilSuccess(Box::new(self))
+    }
+
+    pub fn repeat_until_fail(self) -> Self {
+        UnpoweredTreeDef::RepeatUntilFail(Box::new(self))
+    }
+
+    pub fn succeeder(self) -> Self {
+        UnpoweredTreeDef::Succeeder(Box::new(self))
+    }
+
+    pub fn failer(self) -> Self {
+        UnpoweredTreeDef::Failer(Box::new(self))
+    }
+
+    pub fn inverter(self) -> Self {
+        UnpoweredTreeDef::Inverter(Box::new(self))
+    }
+}
@@ -0,0 +1,110 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{UnpoweredTreeDef, UserNodeDefinition, UserWrapperDefinition};
+use crate::powered::{parse_dsl, to_ron, write_dsl_line, BehaviorTreeDslError, DslNode};
+
+// The same text format as powered::BehaviorTreeDef::from_dsl, with executor blocks and
+// without gas blocks.
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: UserNodeDefinition + Send + Sync + DeserializeOwned,
+    W: UserWrapperDefinition<U> + Send + Sync + DeserializeOwned,
+{
+    pub fn from_dsl(text: &str) -> Result<Self, BehaviorTreeDslError> {
+        Self::from_dsl_node(&parse_dsl(text)?)
+    }
+
+    fn from_dsl_node(node: &DslNode) -> Result<Self, BehaviorTreeDslError> {
+        let Some(keyword) = node.keyword() else {
+            return Ok(UnpoweredTreeDef::User(node.value()?));
+        };
+        let only_child = || -> Result<Box<Self>, BehaviorTreeDslError> {
+            Ok(Box::new(Self::from_dsl_node(node.only_child()?)?))
+        };
+        let all_children = || -> Result<Vec<Self>, BehaviorTreeDslError> {
+            node.children.iter().map(Self::from_dsl_node).collect()
+        };
+        if matches!(keyword, "gas_cost" | "gas_budget") {
+            return Err(node.error(format!("{} is only available in powered trees", keyword)));
+        }
+        if !matches!(keyword, "repeat" | "wrapper") {
+            node.expect_no_argument()?;
+        }
+        Ok(match keyword {
+            "sequence" => UnpoweredTreeDef::Sequence(all_children()?),
+            "selector" => UnpoweredTreeDef::Selector(all_children()?),
+            "executor" => UnpoweredTreeDef::Executor(all_children()?),
+            "repeat" => UnpoweredTreeDef::Repeat(only_child()?, node.number()?),
+            "repeat_until_success" => UnpoweredTreeDef::RepeatUntilSuccess(only_child()?),
+            "repeat_until_fail" => UnpoweredTreeDef::RepeatUntilFail(only_child()?),
+            "succeeder" => UnpoweredTreeDef::Succeeder(only_child()?),
+            "failer" => UnpoweredTreeDef::Failer(only_child()?),
+            "inverter" => UnpoweredTreeDef::Inverter(only_child()?),
+            "wrapper" => UnpoweredTreeDef::Wrapper(node.value()?, all_children()?),
+            _ => unreachable!("the parser only accepts known keywords"),
+        })
+    }
+}
+
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: UserNodeDefinition + Send + Sync + Serialize,
+    W: UserWrapperDefinition<U> + Send + Sync + Serialize,
+{
+    // Writes the definition in the text format, which from_dsl reads back unchanged.
+    pub fn to_dsl(&self) -> Result<String, BehaviorTreeDslError> {
+        let mut text = String::new();
+        self.write_dsl(&mut text, 0)?;
+        Ok(text)
+    }
+
+    fn write_dsl(&self, text: &mut String, depth: usize) -> Result<(), BehaviorTreeDslError> {
+        let (keyword, argument, children) = match self {
+            UnpoweredTreeDef::Sequence(node_defs) => {
+                ("sequence", String::new(), node_defs.iter().collect())
+            }
+            UnpoweredTreeDef::Selector(node_defs) => {
+                ("selector", String::new(), node_defs.iter().collect())
+            }
+            UnpoweredTreeDef::Executor(node_defs) => {
+                ("executor", String::new(), node_defs.iter().collect())
+            }
+            UnpoweredTreeDef::Repeat(node_def, repeats) => {
+                ("repeat", repeats.to_string(), vec![node_def.as_ref()])
+            }
+            UnpoweredTreeDef::RepeatUntilSuccess(node_def) => (
+                "repeat_until_success",
+                String::new(),
+                vec![node_def.as_ref()],
+            ),
+            UnpoweredTreeDef::RepeatUntilFail(node_def) => {
+                ("repeat_until_fail", String::new(), vec![node_def.as_ref()])
+            }
+            UnpoweredTreeDef::Succeeder(node_def) => {
+                ("succeeder", String::new(), vec![node_def.as_ref()])
+            }
+            UnpoweredTreeDef::Failer(node_def) => {
+                ("failer", String::new(), vec![node_def.as_ref()])
+            }
+            UnpoweredTreeDef::Inverter(node_def) => {
+                ("inverter", String::new(), vec![node_def.as_ref()])
+            }
+            UnpoweredTreeDef::User(node_def) => {
+                let value = to_ron(node_def, text)?;
+                write_dsl_line(text, depth, None, &value);
+                return Ok(());
+            }
+            UnpoweredTreeDef::Wrapper(wrapper_def, node_defs) => (
+                "wrapper",
+                to_ron(wrapper_def, text)?,
+                node_defs.iter().collect::<Vec<_>>(),
+            ),
+        };
+        write_dsl_line(text, depth, Some(keyword), &argument);
+        for child in children {
+            child.write_dsl(text, depth + 1)?;
+        }
+        Ok(())
+    }
+}
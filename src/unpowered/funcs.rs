@@ -1,3 +1,9 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::powered::BehaviorTreeAudit;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum UnpoweredFunctionState {
     Waiting,
@@ -16,4 +22,24 @@ pub trait UnpoweredFunction {
         controller: &mut Self::Controller,
     ) -> UnpoweredFunctionState;
     fn reset(self: &mut Self, model: &Self::Model);
+
+    // Nodes without a name of their own show up in audits with an empty name, so their
+    // events can't be told apart. Leaves that audit should keep a name from get_bt_id, as
+    // the built-in nodes do.
+    fn get_name(self: &Self) -> &String {
+        &UNNAMED
+    }
+
+    // Resumes while recording into the audit. Nodes that don't record anything just resume,
+    // so user nodes stay opaque unless they override this. resume_with never audits.
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        _audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        self.resume_with(model, controller)
+    }
 }
+
+static UNNAMED: String = String::new();
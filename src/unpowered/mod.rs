@@ -1,6 +1,17 @@
+mod adapters;
+mod builder;
+#[cfg(feature = "dsl")]
+mod dsl;
 mod funcs;
 mod nodes;
+mod runner;
+#[cfg(feature = "schema")]
+mod schema;
 mod tree_def;
+mod validate;
+mod visit;
+pub use adapters::*;
 pub use funcs::*;
 pub use nodes::*;
+pub use runner::*;
 pub use tree_def::*;
@@ -1,6 +1,8 @@
 use super::super::*;
+use crate::powered::{get_bt_id, BehaviorTreeAudit, BehaviorTreeAuditTrait};
 
 pub struct Executor<M, C> {
+    name: String,
     nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     index: Option<usize>,
     success: bool,
@@ -11,25 +13,23 @@ impl<M, C> Executor<M, C> {
         nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     ) -> Self {
         Executor {
+            name: get_bt_id(),
             nodes,
             index: None,
             success: false,
         }
     }
-}
 
-impl<M: 'static, C: 'static> UnpoweredFunction for Executor<M, C> {
-    type Model = M;
-    type Controller = C;
-    fn resume_with(
+    fn resume_nodes(
         self: &mut Self,
-        model: &Self::Model,
-        controller: &mut Self::Controller,
+        mut resume_node: impl FnMut(
+            &mut Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+        ) -> UnpoweredFunctionState,
     ) -> UnpoweredFunctionState {
         let mut running_index = self.index.unwrap_or(0);
         loop {
             if let Some(node) = self.nodes.get_mut(running_index) {
-                let result = node.resume_with(model, controller);
+                let result = resume_node(node);
                 match result {
                     UnpoweredFunctionState::Failed => {
                         // Move on to the next node.
@@ -54,9 +54,38 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Executor<M, C> {
             }
         }
     }
+}
+
+impl<M: 'static, C: 'static> UnpoweredFunction for Executor<M, C> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        self.resume_nodes(|node| node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result =
+            self.resume_nodes(|node| node.resume_with_audit(model, controller, &mut *audit));
+        audit.exit(&self.name, result.into());
+        result
+    }
 
     fn reset(self: &mut Self, _parameter: &Self::Model) {
         self.index = None;
         self.success = false;
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
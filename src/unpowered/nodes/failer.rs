@@ -1,12 +1,29 @@
 use super::super::*;
+use crate::powered::{get_bt_id, BehaviorTreeAudit, BehaviorTreeAuditTrait};
 
 pub struct Failer<M, C> {
+    name: String,
     node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
 }
 
 impl<M, C> Failer<M, C> {
     pub fn new(node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>) -> Self {
-        Failer { node }
+        Failer {
+            name: get_bt_id(),
+            node,
+        }
+    }
+
+    fn outcome(result: UnpoweredFunctionState) -> UnpoweredFunctionState {
+        match result {
+            UnpoweredFunctionState::Failed | UnpoweredFunctionState::Complete => {
+                return UnpoweredFunctionState::Failed;
+            }
+            result => {
+                // Waiting, NeedsGas
+                return result;
+            }
+        }
     }
 }
 
@@ -18,18 +35,26 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Failer<M, C> {
         model: &Self::Model,
         controller: &mut Self::Controller,
     ) -> UnpoweredFunctionState {
-        match self.node.resume_with(model, controller) {
-            UnpoweredFunctionState::Failed | UnpoweredFunctionState::Complete => {
-                return UnpoweredFunctionState::Failed;
-            }
-            result => {
-                // Waiting, NeedsGas
-                return result;
-            }
-        }
+        Self::outcome(self.node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result = Self::outcome(self.node.resume_with_audit(model, controller, audit));
+        audit.exit(&self.name, result.into());
+        result
     }
 
     fn reset(self: &mut Self, model: &Self::Model) {
         self.node.reset(model);
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
@@ -1,24 +1,21 @@
 use super::super::*;
+use crate::powered::{get_bt_id, BehaviorTreeAudit, BehaviorTreeAuditTrait};
 
 pub struct Inverter<M, C> {
+    name: String,
     node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
 }
 
 impl<M, C> Inverter<M, C> {
     pub fn new(node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>) -> Self {
-        Inverter { node }
+        Inverter {
+            name: get_bt_id(),
+            node,
+        }
     }
-}
 
-impl<M: 'static, C: 'static> UnpoweredFunction for Inverter<M, C> {
-    type Model = M;
-    type Controller = C;
-    fn resume_with(
-        self: &mut Self,
-        model: &Self::Model,
-        controller: &mut Self::Controller,
-    ) -> UnpoweredFunctionState {
-        match self.node.resume_with(model, controller) {
+    fn outcome(result: UnpoweredFunctionState) -> UnpoweredFunctionState {
+        match result {
             UnpoweredFunctionState::Complete => {
                 return UnpoweredFunctionState::Failed;
             }
@@ -31,8 +28,36 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Inverter<M, C> {
             }
         }
     }
+}
+
+impl<M: 'static, C: 'static> UnpoweredFunction for Inverter<M, C> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        Self::outcome(self.node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result = Self::outcome(self.node.resume_with_audit(model, controller, audit));
+        audit.exit(&self.name, result.into());
+        result
+    }
 
     fn reset(self: &mut Self, model: &Self::Model) {
         self.node.reset(model);
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
@@ -1,6 +1,8 @@
 use super::super::*;
+use crate::powered::{get_bt_id, BehaviorTreeAudit, BehaviorTreeAuditTrait};
 
 pub struct Repeat<M, C> {
+    name: String,
     node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
     runs: usize,
     runs_left: usize,
@@ -12,23 +14,21 @@ impl<M, C> Repeat<M, C> {
         runs: usize,
     ) -> Self {
         Repeat {
+            name: get_bt_id(),
             node,
             runs,
             runs_left: runs,
         }
     }
-}
 
-impl<M: 'static, C: 'static> UnpoweredFunction for Repeat<M, C> {
-    type Model = M;
-    type Controller = C;
-    fn resume_with(
+    fn resume_node(
         self: &mut Self,
-        model: &Self::Model,
-        controller: &mut Self::Controller,
+        mut resume_node: impl FnMut(
+            &mut Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+        ) -> UnpoweredFunctionState,
     ) -> UnpoweredFunctionState {
         while self.runs_left > 0 {
-            let result = self.node.resume_with(model, controller);
+            let result = resume_node(&mut self.node);
             match result {
                 UnpoweredFunctionState::Failed => {
                     self.runs_left = self.runs;
@@ -46,31 +46,62 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Repeat<M, C> {
         self.runs_left = self.runs;
         return UnpoweredFunctionState::Complete;
     }
+}
+
+impl<M: 'static, C: 'static> UnpoweredFunction for Repeat<M, C> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        self.resume_node(|node| node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result =
+            self.resume_node(|node| node.resume_with_audit(model, controller, &mut *audit));
+        audit.exit(&self.name, result.into());
+        result
+    }
 
     fn reset(self: &mut Self, model: &Self::Model) {
         self.runs_left = self.runs;
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
+
 pub struct RepeatUntilFail<M, C> {
+    name: String,
     node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
 }
 
 impl<M, C> RepeatUntilFail<M, C> {
     pub fn new(node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>) -> Self {
-        RepeatUntilFail { node }
+        RepeatUntilFail {
+            name: get_bt_id(),
+            node,
+        }
     }
-}
 
-impl<M: 'static, C: 'static> UnpoweredFunction for RepeatUntilFail<M, C> {
-    type Model = M;
-    type Controller = C;
-    fn resume_with(
+    fn resume_node(
         self: &mut Self,
-        model: &Self::Model,
-        controller: &mut Self::Controller,
+        mut resume_node: impl FnMut(
+            &mut Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+        ) -> UnpoweredFunctionState,
     ) -> UnpoweredFunctionState {
         loop {
-            let result = self.node.resume_with(model, controller);
+            let result = resume_node(&mut self.node);
             match result {
                 UnpoweredFunctionState::Failed => {
                     return UnpoweredFunctionState::Complete;
@@ -86,32 +117,62 @@ impl<M: 'static, C: 'static> UnpoweredFunction for RepeatUntilFail<M, C> {
             }
         }
     }
+}
+
+impl<M: 'static, C: 'static> UnpoweredFunction for RepeatUntilFail<M, C> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        self.resume_node(|node| node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result =
+            self.resume_node(|node| node.resume_with_audit(model, controller, &mut *audit));
+        audit.exit(&self.name, result.into());
+        result
+    }
 
     fn reset(self: &mut Self, model: &Self::Model) {
         // Nothing to do.
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
 
 pub struct RepeatUntilSuccess<M, C> {
+    name: String,
     node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
 }
 
 impl<M, C> RepeatUntilSuccess<M, C> {
     pub fn new(node: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>) -> Self {
-        RepeatUntilSuccess { node }
+        RepeatUntilSuccess {
+            name: get_bt_id(),
+            node,
+        }
     }
-}
 
-impl<M: 'static, C: 'static> UnpoweredFunction for RepeatUntilSuccess<M, C> {
-    type Model = M;
-    type Controller = C;
-    fn resume_with(
+    fn resume_node(
         self: &mut Self,
-        model: &Self::Model,
-        controller: &mut Self::Controller,
+        mut resume_node: impl FnMut(
+            &mut Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+        ) -> UnpoweredFunctionState,
     ) -> UnpoweredFunctionState {
         loop {
-            let result = self.node.resume_with(model, controller);
+            let result = resume_node(&mut self.node);
             match result {
                 UnpoweredFunctionState::Complete => {
                     return UnpoweredFunctionState::Complete;
@@ -127,8 +188,37 @@ impl<M: 'static, C: 'static> UnpoweredFunction for RepeatUntilSuccess<M, C> {
             }
         }
     }
+}
+
+impl<M: 'static, C: 'static> UnpoweredFunction for RepeatUntilSuccess<M, C> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        self.resume_node(|node| node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result =
+            self.resume_node(|node| node.resume_with_audit(model, controller, &mut *audit));
+        audit.exit(&self.name, result.into());
+        result
+    }
 
     fn reset(self: &mut Self, model: &Self::Model) {
         // Nothing to do.
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
@@ -1,6 +1,8 @@
 use super::super::*;
+use crate::powered::{get_bt_id, BehaviorTreeAudit, BehaviorTreeAuditTrait};
 
 pub struct Sequence<M, C> {
+    name: String,
     nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     index: Option<usize>,
 }
@@ -9,22 +11,25 @@ impl<M, C> Sequence<M, C> {
     pub fn new(
         nodes: Vec<Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>>,
     ) -> Self {
-        Sequence { nodes, index: None }
+        Sequence {
+            name: get_bt_id(),
+            nodes,
+            index: None,
+        }
     }
-}
 
-impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
-    type Model = M;
-    type Controller = C;
-    fn resume_with(
+    // Shared by the audited and plain paths, which differ only in how children are resumed.
+    // The other nodes split their resumes the same way.
+    fn resume_nodes(
         self: &mut Self,
-        model: &Self::Model,
-        controller: &mut Self::Controller,
+        mut resume_node: impl FnMut(
+            &mut Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+        ) -> UnpoweredFunctionState,
     ) -> UnpoweredFunctionState {
         let mut running_index = self.index.unwrap_or(0);
         loop {
             if let Some(node) = self.nodes.get_mut(running_index) {
-                let result = node.resume_with(model, controller);
+                let result = resume_node(node);
                 match result {
                     UnpoweredFunctionState::Complete => {
                         // Move on to the next node.
@@ -46,8 +51,37 @@ impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
             }
         }
     }
+}
+
+impl<M: 'static, C: 'static> UnpoweredFunction for Sequence<M, C> {
+    type Model = M;
+    type Controller = C;
+    fn resume_with(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+    ) -> UnpoweredFunctionState {
+        self.resume_nodes(|node| node.resume_with(model, controller))
+    }
+
+    fn resume_with_audit(
+        self: &mut Self,
+        model: &Self::Model,
+        controller: &mut Self::Controller,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.enter(&self.name);
+        let result =
+            self.resume_nodes(|node| node.resume_with_audit(model, controller, &mut *audit));
+        audit.exit(&self.name, result.into());
+        result
+    }
 
     fn reset(self: &mut Self, model: &Self::Model) {
         self.index = None;
     }
+
+    fn get_name(self: &Self) -> &String {
+        &self.name
+    }
 }
@@ -0,0 +1,118 @@
+use super::{UnpoweredFunction, UnpoweredFunctionState};
+use crate::powered::{AuditPolicy, BehaviorTreeAudit, RootPolicy};
+
+// Owns an unpowered tree and the loop around it: resuming the root once per tick and
+// restarting it according to the root policy, and recording audits. Mirrors
+// powered::TreeRunner.
+pub struct UnpoweredTreeRunner<M, C> {
+    tree: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>,
+    root_policy: RootPolicy,
+    audit_policy: AuditPolicy,
+    audit: Option<BehaviorTreeAudit>,
+    ticks: u64,
+    last_state: Option<UnpoweredFunctionState>,
+    finished: bool,
+}
+
+impl<M: 'static, C: 'static> UnpoweredTreeRunner<M, C> {
+    // A runner that loops forever and doesn't audit.
+    pub fn new(tree: Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync>) -> Self {
+        UnpoweredTreeRunner {
+            tree,
+            root_policy: RootPolicy::Loop,
+            audit_policy: AuditPolicy::Off,
+            audit: None,
+            ticks: 0,
+            last_state: None,
+            finished: false,
+        }
+    }
+
+    pub fn with_root_policy(mut self, root_policy: RootPolicy) -> Self {
+        self.root_policy = root_policy;
+        self
+    }
+
+    pub fn with_audit(mut self, audit_policy: AuditPolicy) -> Self {
+        self.audit_policy = audit_policy;
+        self.audit = match audit_policy {
+            AuditPolicy::Off => None,
+            AuditPolicy::PerTick => Some(BehaviorTreeAudit::new()),
+            AuditPolicy::Retain(limit) => Some(BehaviorTreeAudit::bounded(limit)),
+        };
+        self
+    }
+
+    // Runs one tick, returning the root's state. Once finished, the final state is
+    // returned without resuming the tree.
+    pub fn tick(&mut self, model: &M, controller: &mut C) -> UnpoweredFunctionState {
+        if let Some(last_state) = self.last_state.filter(|_| self.finished) {
+            return last_state;
+        }
+        let state = if let Some(audit) = &mut self.audit {
+            if self.audit_policy == AuditPolicy::PerTick {
+                audit.clear();
+            }
+            audit.start_tick();
+            self.tree
+                .resume_with_audit(model, controller, &mut self.audit)
+        } else {
+            self.tree.resume_with(model, controller)
+        };
+        self.ticks += 1;
+        self.last_state = Some(state);
+        match (state, self.root_policy) {
+            (UnpoweredFunctionState::Complete, RootPolicy::Loop)
+            | (UnpoweredFunctionState::Failed, RootPolicy::Loop)
+            | (UnpoweredFunctionState::Failed, RootPolicy::RestartOnFailure) => {
+                self.tree.reset(model)
+            }
+            (UnpoweredFunctionState::Complete, _) | (UnpoweredFunctionState::Failed, _) => {
+                self.finished = true
+            }
+            _ => {}
+        }
+        state
+    }
+
+    // Resets the tree and clears the finished flag, so the next tick starts from the root.
+    pub fn restart(&mut self, model: &M) {
+        self.tree.reset(model);
+        self.finished = false;
+        self.last_state = None;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // The number of ticks run so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn last_state(&self) -> Option<UnpoweredFunctionState> {
+        self.last_state
+    }
+
+    pub fn audit(&self) -> Option<&BehaviorTreeAudit> {
+        self.audit.as_ref()
+    }
+
+    pub fn take_audit(&mut self) -> Option<BehaviorTreeAudit> {
+        let audit = self.audit.as_mut()?;
+        let taken = audit.clone();
+        audit.clear();
+        Some(taken)
+    }
+
+    pub fn tree(&self) -> &(dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync) {
+        self.tree.as_ref()
+    }
+
+    pub fn tree_mut(
+        &mut self,
+    ) -> &mut Box<dyn UnpoweredFunction<Model = M, Controller = C> + Send + Sync> {
+        &mut self.tree
+    }
+}
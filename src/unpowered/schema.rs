@@ -0,0 +1,32 @@
+use serde_json::Value;
+
+use super::{UnpoweredTreeDef, UserNodeDefinition, UserWrapperDefinition};
+use crate::powered::{json_schema, SchemaVariant};
+
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: UserNodeDefinition + Send + Sync,
+    W: UserWrapperDefinition<U> + Send + Sync,
+{
+    // The same as powered::BehaviorTreeDef::json_schema, with Executor and without gas nodes.
+    pub fn json_schema(user_schema: Value, wrapper_schema: Value) -> Value {
+        json_schema(
+            "UnpoweredTreeDef",
+            &[
+                ("Sequence", SchemaVariant::Children),
+                ("Selector", SchemaVariant::Children),
+                ("Executor", SchemaVariant::Children),
+                ("Repeat", SchemaVariant::ChildAndCount(usize::MAX as u64)),
+                ("RepeatUntilSuccess", SchemaVariant::Child),
+                ("RepeatUntilFail", SchemaVariant::Child),
+                ("Succeeder", SchemaVariant::Child),
+                ("Failer", SchemaVariant::Child),
+                ("Inverter", SchemaVariant::Child),
+                ("User", SchemaVariant::User),
+                ("Wrapper", SchemaVariant::Wrapper),
+            ],
+            user_schema,
+            wrapper_schema,
+        )
+    }
+}
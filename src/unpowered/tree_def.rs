@@ -1,8 +1,11 @@
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::{nodes::*, UnpoweredFunction};
+use crate::powered::{BehaviorTreeCreateError, BehaviorTreeNodeError, BehaviorTreePath};
 
-#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, PartialEq)]
 pub enum UnpoweredTreeDef<
     U: UserNodeDefinition + Sync + Send,
     W: UserWrapperDefinition<U> + Sync + Send,
@@ -27,6 +30,24 @@ pub trait UserNodeDefinition {
     fn create_node(
         &self,
     ) -> Box<dyn UnpoweredFunction<Model = Self::Model, Controller = Self::Controller> + Send + Sync>;
+
+    // Creates the node, or says why it can't. Defaults to create_node, which can't fail.
+    fn try_create_node(
+        &self,
+    ) -> Result<
+        Box<
+            dyn UnpoweredFunction<Model = Self::Model, Controller = Self::Controller> + Send + Sync,
+        >,
+        BehaviorTreeNodeError,
+    > {
+        Ok(self.create_node())
+    }
+
+    // How errors show this definition. Defaults to the name of its type; definitions
+    // that are Debug can return format!("{:?}", self) instead.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
 }
 
 impl<M: 'static, C: 'static, D: 'static> UserNodeDefinition for D
@@ -51,6 +72,29 @@ pub trait UserWrapperDefinition<U: UserNodeDefinition> {
             Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
         >,
     ) -> Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>;
+
+    // Creates the node, or says why it can't. Defaults to create_node_and_wrap, which can't fail.
+    fn try_create_node_and_wrap(
+        &self,
+        nodes: Vec<
+            Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
+        >,
+    ) -> Result<
+        Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
+        BehaviorTreeNodeError,
+    > {
+        Ok(self.create_node_and_wrap(nodes))
+    }
+
+    // How errors show this definition, like UserNodeDefinition::describe.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_string()
+    }
+
+    // Whether this only stands in for a real wrapper and can't create nodes, like ().
+    fn is_placeholder(&self) -> bool {
+        false
+    }
 }
 
 impl<U: UserNodeDefinition> UserWrapperDefinition<U> for () {
@@ -63,69 +107,153 @@ impl<U: UserNodeDefinition> UserWrapperDefinition<U> for () {
     {
         panic!("Cannot create a wrapper with no definition");
     }
+
+    fn try_create_node_and_wrap(
+        &self,
+        _nodes: Vec<
+            Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
+        >,
+    ) -> Result<
+        Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
+        BehaviorTreeNodeError,
+    > {
+        Err("Cannot create a wrapper with no definition".into())
+    }
+
+    fn is_placeholder(&self) -> bool {
+        true
+    }
 }
 
 impl<U: UserNodeDefinition + Send + Sync, W: UserWrapperDefinition<U> + Send + Sync>
     UnpoweredTreeDef<U, W>
 {
+    // The name of the variant, e.g. "Sequence" or "User".
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            UnpoweredTreeDef::Sequence(_) => "Sequence",
+            UnpoweredTreeDef::Selector(_) => "Selector",
+            UnpoweredTreeDef::Executor(_) => "Executor",
+            UnpoweredTreeDef::Repeat(_, _) => "Repeat",
+            UnpoweredTreeDef::RepeatUntilSuccess(_) => "RepeatUntilSuccess",
+            UnpoweredTreeDef::RepeatUntilFail(_) => "RepeatUntilFail",
+            UnpoweredTreeDef::Succeeder(_) => "Succeeder",
+            UnpoweredTreeDef::Failer(_) => "Failer",
+            UnpoweredTreeDef::Inverter(_) => "Inverter",
+            UnpoweredTreeDef::User(_) => "User",
+            UnpoweredTreeDef::Wrapper(_, _) => "Wrapper",
+        }
+    }
+
+    // The direct child definitions, in definition order.
+    pub fn children(&self) -> &[UnpoweredTreeDef<U, W>] {
+        match self {
+            UnpoweredTreeDef::Sequence(node_defs)
+            | UnpoweredTreeDef::Selector(node_defs)
+            | UnpoweredTreeDef::Executor(node_defs)
+            | UnpoweredTreeDef::Wrapper(_, node_defs) => node_defs,
+            UnpoweredTreeDef::Repeat(node_def, _)
+            | UnpoweredTreeDef::RepeatUntilSuccess(node_def)
+            | UnpoweredTreeDef::RepeatUntilFail(node_def)
+            | UnpoweredTreeDef::Succeeder(node_def)
+            | UnpoweredTreeDef::Failer(node_def)
+            | UnpoweredTreeDef::Inverter(node_def) => std::slice::from_ref(node_def.as_ref()),
+            UnpoweredTreeDef::User(_) => &[],
+        }
+    }
+
+    // The definition at the given path of child indices, if there is one.
+    pub fn get(&self, path: &[usize]) -> Option<&UnpoweredTreeDef<U, W>> {
+        match path.split_first() {
+            Some((index, rest)) => self.children().get(*index)?.get(rest),
+            None => Some(self),
+        }
+    }
+
+    pub fn children_mut(&mut self) -> &mut [UnpoweredTreeDef<U, W>] {
+        match self {
+            UnpoweredTreeDef::Sequence(node_defs)
+            | UnpoweredTreeDef::Selector(node_defs)
+            | UnpoweredTreeDef::Executor(node_defs)
+            | UnpoweredTreeDef::Wrapper(_, node_defs) => node_defs,
+            UnpoweredTreeDef::Repeat(node_def, _)
+            | UnpoweredTreeDef::RepeatUntilSuccess(node_def)
+            | UnpoweredTreeDef::RepeatUntilFail(node_def)
+            | UnpoweredTreeDef::Succeeder(node_def)
+            | UnpoweredTreeDef::Failer(node_def)
+            | UnpoweredTreeDef::Inverter(node_def) => std::slice::from_mut(node_def.as_mut()),
+            UnpoweredTreeDef::User(_) => &mut [],
+        }
+    }
+
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut UnpoweredTreeDef<U, W>> {
+        match path.split_first() {
+            Some((index, rest)) => self.children_mut().get_mut(*index)?.get_mut(rest),
+            None => Some(self),
+        }
+    }
+
+    // Creates the tree, panicking if a user or wrapper definition can't create its node.
+    // See try_create_tree.
     pub fn create_tree(
         &self,
     ) -> Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>
     {
-        match self {
-            UnpoweredTreeDef::Sequence(node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                Box::new(Sequence::new(nodes))
-            }
-            UnpoweredTreeDef::Selector(node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                Box::new(Selector::new(nodes))
-            }
-            UnpoweredTreeDef::Executor(node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                Box::new(Executor::new(nodes))
-            }
-            UnpoweredTreeDef::Repeat(node_def, repeats) => {
-                let node = node_def.create_tree();
-                Box::new(Repeat::new(node, *repeats))
-            }
-            UnpoweredTreeDef::RepeatUntilFail(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(RepeatUntilFail::new(node))
-            }
-            UnpoweredTreeDef::RepeatUntilSuccess(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(RepeatUntilSuccess::new(node))
-            }
-            UnpoweredTreeDef::Succeeder(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(Succeeder::new(node))
-            }
-            UnpoweredTreeDef::Inverter(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(Inverter::new(node))
-            }
-            UnpoweredTreeDef::Failer(node_def) => {
-                let node = node_def.create_tree();
-                Box::new(Failer::new(node))
-            }
-            UnpoweredTreeDef::User(node_def) => node_def.create_node(),
-            UnpoweredTreeDef::Wrapper(wrapper_def, node_defs) => {
-                let nodes = node_defs
-                    .iter()
-                    .map(|node_def| node_def.create_tree())
-                    .collect();
-                wrapper_def.create_node_and_wrap(nodes)
-            }
+        self.try_create_tree()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Creates the tree, or reports the first user or wrapper definition that couldn't
+    // create its node.
+    pub fn try_create_tree(
+        &self,
+    ) -> Result<
+        Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
+        BehaviorTreeCreateError,
+    > {
+        self.try_create_tree_at(&mut Vec::new())
+    }
+
+    fn try_create_tree_at(
+        &self,
+        path: &mut BehaviorTreePath,
+    ) -> Result<
+        Box<dyn UnpoweredFunction<Model = U::Model, Controller = U::Controller> + Send + Sync>,
+        BehaviorTreeCreateError,
+    > {
+        let mut children = Vec::new();
+        for (index, node_def) in self.children().iter().enumerate() {
+            path.push(index);
+            children.push(node_def.try_create_tree_at(path)?);
+            path.pop();
         }
+        let failed = |source| BehaviorTreeCreateError {
+            path: path.clone(),
+            kind: self.kind_name(),
+            node: match self {
+                UnpoweredTreeDef::User(node_def) => node_def.describe(),
+                UnpoweredTreeDef::Wrapper(wrapper_def, _) => wrapper_def.describe(),
+                _ => String::new(),
+            },
+            source,
+        };
+        let mut only_child = || children.pop().unwrap();
+        Ok(match self {
+            UnpoweredTreeDef::Sequence(_) => Box::new(Sequence::new(children)),
+            UnpoweredTreeDef::Selector(_) => Box::new(Selector::new(children)),
+            UnpoweredTreeDef::Executor(_) => Box::new(Executor::new(children)),
+            UnpoweredTreeDef::Repeat(_, repeats) => Box::new(Repeat::new(only_child(), *repeats)),
+            UnpoweredTreeDef::RepeatUntilFail(_) => Box::new(RepeatUntilFail::new(only_child())),
+            UnpoweredTreeDef::RepeatUntilSuccess(_) => {
+                Box::new(RepeatUntilSuccess::new(only_child()))
+            }
+            UnpoweredTreeDef::Succeeder(_) => Box::new(Succeeder::new(only_child())),
+            UnpoweredTreeDef::Inverter(_) => Box::new(Inverter::new(only_child())),
+            UnpoweredTreeDef::Failer(_) => Box::new(Failer::new(only_child())),
+            UnpoweredTreeDef::User(node_def) => node_def.try_create_node().map_err(failed)?,
+            UnpoweredTreeDef::Wrapper(wrapper_def, _) => wrapper_def
+                .try_create_node_and_wrap(children)
+                .map_err(failed)?,
+        })
     }
 }
@@ -0,0 +1,129 @@
+use super::{UnpoweredTreeDef, UserNodeDefinition, UserWrapperDefinition};
+use crate::powered::{BehaviorTreeDiagnostic, BehaviorTreePath, BehaviorTreeProblem};
+
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: UserNodeDefinition + Send + Sync,
+    W: UserWrapperDefinition<U> + Send + Sync,
+{
+    // The same checks as powered::BehaviorTreeDef::validate.
+    pub fn validate(&self) -> Vec<BehaviorTreeDiagnostic> {
+        let mut diagnostics = Vec::new();
+        self.validate_at(&mut Vec::new(), &mut diagnostics);
+        diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+        diagnostics
+    }
+
+    fn validate_at(
+        &self,
+        path: &mut BehaviorTreePath,
+        diagnostics: &mut Vec<BehaviorTreeDiagnostic>,
+    ) {
+        let mut report = |path: &BehaviorTreePath, kind, problem| {
+            diagnostics.push(BehaviorTreeDiagnostic {
+                path: path.clone(),
+                kind,
+                problem,
+            })
+        };
+        match self {
+            UnpoweredTreeDef::Sequence(node_defs) | UnpoweredTreeDef::Selector(node_defs) => {
+                if node_defs.is_empty() {
+                    report(path, self.kind_name(), BehaviorTreeProblem::EmptyComposite);
+                }
+                // A Sequence is cut short by a failure, a Selector by a completion.
+                let ending = matches!(self, UnpoweredTreeDef::Selector(_));
+                let cut_off_by = node_defs
+                    .iter()
+                    .position(|node_def| node_def.fixed_outcome() == Some(ending));
+                if let Some(cut_off_by) = cut_off_by {
+                    for (index, node_def) in node_defs.iter().enumerate().skip(cut_off_by + 1) {
+                        path.push(index);
+                        report(
+                            path,
+                            node_def.kind_name(),
+                            BehaviorTreeProblem::Unreachable { cut_off_by },
+                        );
+                        path.pop();
+                    }
+                }
+            }
+            UnpoweredTreeDef::Executor(node_defs) if node_defs.is_empty() => {
+                report(path, self.kind_name(), BehaviorTreeProblem::EmptyComposite)
+            }
+            UnpoweredTreeDef::Repeat(_, 0) => {
+                report(path, self.kind_name(), BehaviorTreeProblem::ZeroRepeats)
+            }
+            UnpoweredTreeDef::RepeatUntilSuccess(node_def)
+                if node_def.fixed_outcome() == Some(false) =>
+            {
+                report(path, self.kind_name(), BehaviorTreeProblem::EndlessRepeat)
+            }
+            UnpoweredTreeDef::RepeatUntilFail(node_def)
+                if node_def.fixed_outcome() == Some(true) =>
+            {
+                report(path, self.kind_name(), BehaviorTreeProblem::EndlessRepeat)
+            }
+            UnpoweredTreeDef::Wrapper(wrapper_def, _) if wrapper_def.is_placeholder() => report(
+                path,
+                self.kind_name(),
+                BehaviorTreeProblem::PlaceholderWrapper,
+            ),
+            _ => {}
+        }
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            child.validate_at(path, diagnostics);
+            path.pop();
+        }
+    }
+
+    // Some(true) if the node always completes when it finishes, Some(false) if it always
+    // fails, and None if it depends on user nodes.
+    fn fixed_outcome(&self) -> Option<bool> {
+        match self {
+            UnpoweredTreeDef::Sequence(node_defs) => first_decisive(node_defs, false),
+            UnpoweredTreeDef::Selector(node_defs) => first_decisive(node_defs, true),
+            // An Executor completes if any child did.
+            UnpoweredTreeDef::Executor(node_defs) => {
+                let outcomes: Vec<Option<bool>> = node_defs
+                    .iter()
+                    .map(|node_def| node_def.fixed_outcome())
+                    .collect();
+                if outcomes.contains(&Some(true)) {
+                    Some(true)
+                } else if outcomes.iter().all(|outcome| *outcome == Some(false)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            UnpoweredTreeDef::Repeat(_, 0) => Some(true),
+            UnpoweredTreeDef::Repeat(node_def, _) => node_def.fixed_outcome(),
+            UnpoweredTreeDef::RepeatUntilSuccess(_)
+            | UnpoweredTreeDef::RepeatUntilFail(_)
+            | UnpoweredTreeDef::Succeeder(_) => Some(true),
+            UnpoweredTreeDef::Failer(_) => Some(false),
+            UnpoweredTreeDef::Inverter(node_def) => {
+                node_def.fixed_outcome().map(|outcome| !outcome)
+            }
+            UnpoweredTreeDef::User(_) | UnpoweredTreeDef::Wrapper(_, _) => None,
+        }
+    }
+}
+
+// See powered::BehaviorTreeDef's first_decisive.
+fn first_decisive<U, W>(node_defs: &[UnpoweredTreeDef<U, W>], decisive: bool) -> Option<bool>
+where
+    U: UserNodeDefinition + Send + Sync,
+    W: UserWrapperDefinition<U> + Send + Sync,
+{
+    for node_def in node_defs {
+        match node_def.fixed_outcome() {
+            Some(outcome) if outcome == decisive => return Some(decisive),
+            Some(_) => {}
+            None => return None,
+        }
+    }
+    Some(!decisive)
+}
@@ -0,0 +1,109 @@
+use super::{UnpoweredTreeDef, UserNodeDefinition, UserWrapperDefinition};
+use crate::powered::BehaviorTreePath;
+
+impl<U, W> UnpoweredTreeDef<U, W>
+where
+    U: UserNodeDefinition + Send + Sync,
+    W: UserWrapperDefinition<U> + Send + Sync,
+{
+    // Calls the visitor with every definition and its path, parents before children.
+    pub fn visit(&self, visitor: &mut impl FnMut(&BehaviorTreePath, &UnpoweredTreeDef<U, W>)) {
+        self.visit_at(&mut Vec::new(), visitor);
+    }
+
+    fn visit_at(
+        &self,
+        path: &mut BehaviorTreePath,
+        visitor: &mut impl FnMut(&BehaviorTreePath, &UnpoweredTreeDef<U, W>),
+    ) {
+        visitor(path, self);
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            child.visit_at(path, visitor);
+            path.pop();
+        }
+    }
+
+    // Like visit, but the visitor can change definitions or replace them outright. A parent
+    // is visited before its children, so the children of whatever it's left as are visited.
+    pub fn visit_mut(
+        &mut self,
+        visitor: &mut impl FnMut(&BehaviorTreePath, &mut UnpoweredTreeDef<U, W>),
+    ) {
+        self.visit_mut_at(&mut Vec::new(), visitor);
+    }
+
+    fn visit_mut_at(
+        &mut self,
+        path: &mut BehaviorTreePath,
+        visitor: &mut impl FnMut(&BehaviorTreePath, &mut UnpoweredTreeDef<U, W>),
+    ) {
+        visitor(path, self);
+        for (index, child) in self.children_mut().iter_mut().enumerate() {
+            path.push(index);
+            child.visit_mut_at(path, visitor);
+            path.pop();
+        }
+    }
+
+    // Combines the definitions bottom up: the folder gets each definition and its path along
+    // with the results for its children, in order, and the result for the root is returned.
+    pub fn fold<T>(
+        &self,
+        folder: &mut impl FnMut(&BehaviorTreePath, &UnpoweredTreeDef<U, W>, Vec<T>) -> T,
+    ) -> T {
+        self.fold_at(&mut Vec::new(), folder)
+    }
+
+    fn fold_at<T>(
+        &self,
+        path: &mut BehaviorTreePath,
+        folder: &mut impl FnMut(&BehaviorTreePath, &UnpoweredTreeDef<U, W>, Vec<T>) -> T,
+    ) -> T {
+        let mut children = Vec::new();
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            children.push(child.fold_at(path, folder));
+            path.pop();
+        }
+        folder(path, self, children)
+    }
+
+    // Converts the user and wrapper definitions, keeping everything else.
+    pub fn map_user<U2, W2>(
+        &self,
+        map_user: &impl Fn(&U) -> U2,
+        map_wrapper: &impl Fn(&W) -> W2,
+    ) -> UnpoweredTreeDef<U2, W2>
+    where
+        U2: UserNodeDefinition + Send + Sync,
+        W2: UserWrapperDefinition<U2> + Send + Sync,
+    {
+        self.fold(
+            &mut |_, node_def, mut children: Vec<UnpoweredTreeDef<U2, W2>>| {
+                let mut child = || Box::new(children.pop().unwrap());
+                match node_def {
+                    UnpoweredTreeDef::Repeat(_, repeats) => {
+                        UnpoweredTreeDef::Repeat(child(), *repeats)
+                    }
+                    UnpoweredTreeDef::RepeatUntilSuccess(_) => {
+                        UnpoweredTreeDef::RepeatUntilSuccess(child())
+                    }
+                    UnpoweredTreeDef::RepeatUntilFail(_) => {
+                        UnpoweredTreeDef::RepeatUntilFail(child())
+                    }
+                    UnpoweredTreeDef::Succeeder(_) => UnpoweredTreeDef::Succeeder(child()),
+                    UnpoweredTreeDef::Failer(_) => UnpoweredTreeDef::Failer(child()),
+                    UnpoweredTreeDef::Inverter(_) => UnpoweredTreeDef::Inverter(child()),
+                    UnpoweredTreeDef::User(node_def) => UnpoweredTreeDef::User(map_user(node_def)),
+                    UnpoweredTreeDef::Sequence(_) => UnpoweredTreeDef::Sequence(children),
+                    UnpoweredTreeDef::Selector(_) => UnpoweredTreeDef::Selector(children),
+                    UnpoweredTreeDef::Executor(_) => UnpoweredTreeDef::Executor(children),
+                    UnpoweredTreeDef::Wrapper(wrapper_def, _) => {
+                        UnpoweredTreeDef::Wrapper(map_wrapper(wrapper_def), children)
+                    }
+                }
+            },
+        )
+    }
+}
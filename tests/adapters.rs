@@ -0,0 +1,182 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+use behavior_bark::unpowered::{
+    PoweredNode, UnpoweredFunction, UnpoweredFunctionState, UnpoweredTreeDef,
+};
+use behavior_bark::unpowered_bt;
+
+use common::*;
+use BehaviorTreeState::*;
+
+fn to_unpowered_state(state: &BehaviorTreeState) -> UnpoweredFunctionState {
+    match state {
+        Complete => UnpoweredFunctionState::Complete,
+        Failed => UnpoweredFunctionState::Failed,
+        Waiting => UnpoweredFunctionState::Waiting,
+        WaitingForGas => panic!("unpowered leaves can't wait for gas"),
+    }
+}
+
+fn to_unpowered(leaf: &Scripted) -> UnpoweredScripted {
+    let script: Vec<_> = leaf.script.iter().map(to_unpowered_state).collect();
+    UnpoweredScripted::new(leaf.id, &script)
+}
+
+fn to_powered(leaf: &UnpoweredScripted) -> Scripted {
+    let script: Vec<_> = leaf.script.iter().map(|state| (*state).into()).collect();
+    Scripted::new(leaf.id, &script)
+}
+
+fn every_shared_kind() -> Def {
+    bt! {
+        sequence {
+            selector { leaf(Scripted::fails(1)) leaf(Scripted::new(2, &[Waiting, Complete])) }
+            repeat(2) { inverter { leaf(Scripted::fails(3)) } }
+            repeat_until_success { inverter { leaf(Scripted::new(4, &[Complete, Failed])) } }
+            repeat_until_fail { leaf(Scripted::new(5, &[Complete, Failed])) }
+            succeeder { failer { leaf(Scripted::completes(7)) } }
+            wrapper(InOrder::Showing) { leaf(Scripted::completes(6)) }
+        }
+    }
+}
+
+#[test]
+fn definitions_convert_both_ways_unchanged() {
+    let def = every_shared_kind();
+    let unpowered: UnpoweredDef = def.to_unpowered(&to_unpowered, &|_| ()).unwrap();
+    assert!(matches!(unpowered, UnpoweredTreeDef::Sequence(ref nodes) if nodes.len() == 6));
+    let back: Def = unpowered
+        .to_powered(&to_powered, &|_| InOrder::Showing)
+        .unwrap();
+    assert!(back == def);
+}
+
+#[test]
+fn nodes_without_a_counterpart_are_reported_by_path() {
+    let def: Def = bt! {
+        sequence {
+            leaf(Scripted::completes(1))
+            selector { gas_cost(2) { leaf(Scripted::completes(2)) } }
+        }
+    };
+    let error = def.to_unpowered(&to_unpowered, &|_| ()).err().unwrap();
+    assert_eq!(
+        error,
+        BehaviorTreeDefConversionError {
+            path: vec![1, 0],
+            kind: "GasCost",
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "GasCost at [1, 0] has no equivalent in the other tree flavour"
+    );
+
+    let def: Def = bt! { inverter { gas_budget(5) { leaf(Scripted::completes(1)) } } };
+    let error = def.to_unpowered(&to_unpowered, &|_| ()).err().unwrap();
+    assert_eq!((error.path, error.kind), (vec![0], "GasBudget"));
+
+    let def: UnpoweredDef = unpowered_bt! {
+        selector { leaf(UnpoweredScripted::fails(1)) executor { } }
+    };
+    let error = def
+        .to_powered(&to_powered, &|_| InOrder::Showing)
+        .err()
+        .unwrap();
+    assert_eq!((error.path, error.kind), (vec![1], "Executor"));
+}
+
+#[test]
+fn converted_trees_run_like_the_trees_they_came_from() {
+    let def = every_shared_kind();
+    let (states, log) = run_with_gas_costs(&def, &BehaviorTreeGasCosts::free(), 12);
+
+    let unpowered: UnpoweredDef = def.to_unpowered(&to_unpowered, &|_| ()).unwrap();
+    // The wrapper has no unpowered counterpart to create, so swap it for what it does.
+    let unpowered = match unpowered {
+        UnpoweredTreeDef::Sequence(mut nodes) => {
+            let UnpoweredTreeDef::Wrapper(_, children) = nodes.pop().unwrap() else {
+                panic!("the last node should be the wrapper");
+            };
+            nodes.push(UnpoweredTreeDef::Sequence(children));
+            UnpoweredTreeDef::Sequence(nodes)
+        }
+        _ => panic!("the root should be a sequence"),
+    };
+    let mut tree = unpowered.create_tree();
+    let mut unpowered_log = Log::new();
+    let mut unpowered_states = Vec::new();
+    for _ in 0..12 {
+        let state: BehaviorTreeState = tree.resume_with(&(), &mut unpowered_log).into();
+        if matches!(state, Complete | Failed) {
+            tree.reset(&());
+        }
+        unpowered_states.push(state);
+    }
+    assert_eq!(unpowered_states, states);
+    assert_eq!(unpowered_log, log);
+}
+
+#[test]
+fn powered_nodes_start_each_resume_with_fresh_gas() {
+    let def: Def = bt! { repeat(3) { leaf(Scripted::completes(1)) } };
+    let mut node = PoweredNode::new(def.create_tree(), Some(2));
+    let mut log = Log::new();
+    // Each entry into the leaf costs one, so a budget of two can't finish three repeats.
+    assert_eq!(
+        node.resume_with(&(), &mut log),
+        UnpoweredFunctionState::Waiting
+    );
+    assert_eq!(log.len(), 2);
+    assert_eq!(
+        node.resume_with(&(), &mut log),
+        UnpoweredFunctionState::Complete
+    );
+    assert_eq!(log.len(), 3);
+}
+
+#[test]
+fn powered_nodes_audit_the_tree_they_run() {
+    let def: Def = bt! { sequence { leaf(Scripted::completes(1)) } };
+    let tree = def.create_tree();
+    let name = tree.get_name().clone();
+    let mut node = PoweredNode::new(tree, None);
+    assert_eq!(node.get_name(), &name);
+
+    let mut audit = Some(BehaviorTreeAudit::new());
+    let state = node.resume_with_audit(&(), &mut Log::new(), &mut audit);
+    assert_eq!(state, UnpoweredFunctionState::Complete);
+    // The sequence and its leaf each enter and exit.
+    assert_eq!(audit.unwrap().len(), 4);
+}
+
+#[test]
+fn unpowered_user_nodes_charge_their_gas_cost() {
+    let def: BehaviorTreeDef<UnpoweredUserNode<UnpoweredScripted>, ()> =
+        BehaviorTreeDef::User(UnpoweredUserNode::new(UnpoweredScripted::completes(1), 3));
+    let mut tree = def.create_tree();
+    let mut log = Log::new();
+
+    let mut gas = Some(2);
+    assert_eq!(
+        tree.resume_with(&(), &mut log, &mut gas, &mut None),
+        WaitingForGas
+    );
+    assert!(log.is_empty());
+
+    let mut gas = Some(3);
+    assert_eq!(
+        tree.resume_with(&(), &mut log, &mut gas, &mut None),
+        Complete
+    );
+    assert_eq!(gas, Some(0));
+    assert_eq!(log, [(1, Complete)]);
+
+    // Unlimited gas never runs out.
+    assert_eq!(
+        tree.resume_with(&(), &mut log, &mut None, &mut None),
+        Complete
+    );
+}
@@ -0,0 +1,142 @@
+use behavior_bark::powered::*;
+
+use BehaviorTreeState::*;
+
+// Records a tick in which the named node is entered, marked and exited.
+fn record_tick(audit: &mut BehaviorTreeAudit, node_name: &str) {
+    let node_name = node_name.to_string();
+    audit.start_tick();
+    audit.enter(&node_name);
+    audit.mark(&node_name);
+    audit.exit(&node_name, Complete);
+}
+
+fn rendered(events: &[&BehaviorTreeMarker]) -> Vec<String> {
+    events.iter().map(|event| event.to_string()).collect()
+}
+
+#[test]
+fn unbounded_audits_keep_everything() {
+    let mut audit = BehaviorTreeAudit::new();
+    for tick in 0..100 {
+        record_tick(&mut audit, &format!("n{}", tick));
+    }
+    assert_eq!(audit.limit(), BehaviorTreeAuditLimit::Unbounded);
+    assert_eq!(audit.len(), 400);
+    assert_eq!(audit.dropped_events(), 0);
+    assert_eq!(audit.current_tick(), 100);
+}
+
+#[test]
+fn event_limits_drop_the_oldest_events() {
+    let mut audit = BehaviorTreeAudit::bounded(BehaviorTreeAuditLimit::Events(6));
+    record_tick(&mut audit, "a");
+    assert_eq!(audit.len(), 4);
+    assert_eq!(audit.dropped_events(), 0);
+
+    record_tick(&mut audit, "b");
+    assert_eq!(audit.len(), 6);
+    assert_eq!(audit.dropped_events(), 2);
+    let events: Vec<_> = audit.events().collect();
+    assert_eq!(
+        rendered(&events),
+        [
+            "mark a",
+            "exit a Complete",
+            "tick 2",
+            "enter b",
+            "mark b",
+            "exit b Complete"
+        ]
+    );
+}
+
+#[test]
+fn event_limits_can_cut_a_tick_in_half() {
+    let mut audit = BehaviorTreeAudit::bounded(BehaviorTreeAuditLimit::Events(3));
+    record_tick(&mut audit, "a");
+    record_tick(&mut audit, "b");
+    // What is left of a tick whose boundary was dropped is grouped under None.
+    let ticks = audit.ticks();
+    assert_eq!(ticks.len(), 1);
+    assert_eq!(ticks[0].0, None);
+    assert_eq!(
+        rendered(&ticks[0].1),
+        ["enter b", "mark b", "exit b Complete"]
+    );
+}
+
+#[test]
+fn tick_limits_drop_whole_ticks() {
+    let mut audit = BehaviorTreeAudit::bounded(BehaviorTreeAuditLimit::Ticks(2));
+    for node_name in ["a", "b", "c", "d"] {
+        record_tick(&mut audit, node_name);
+    }
+    assert_eq!(audit.len(), 8);
+    assert_eq!(audit.dropped_events(), 8);
+    assert_eq!(audit.current_tick(), 4);
+
+    let ticks = audit.ticks();
+    let numbers: Vec<_> = ticks.iter().map(|(tick, _)| *tick).collect();
+    assert_eq!(numbers, [Some(3), Some(4)]);
+    assert_eq!(
+        rendered(&ticks[1].1),
+        ["enter d", "mark d", "exit d Complete"]
+    );
+}
+
+#[test]
+fn tick_limits_keep_the_current_tick_however_long() {
+    let mut audit = BehaviorTreeAudit::bounded(BehaviorTreeAuditLimit::Ticks(1));
+    record_tick(&mut audit, "a");
+    audit.start_tick();
+    for _ in 0..50 {
+        audit.mark(&"b".to_string());
+    }
+    assert_eq!(audit.len(), 51);
+    assert_eq!(audit.dropped_events(), 4);
+    assert_eq!(audit.ticks()[0].0, Some(2));
+}
+
+#[test]
+fn tick_limits_of_zero_keep_the_current_tick() {
+    let mut audit = BehaviorTreeAudit::bounded(BehaviorTreeAuditLimit::Ticks(0));
+    record_tick(&mut audit, "a");
+    record_tick(&mut audit, "b");
+    assert_eq!(audit.len(), 4);
+    assert_eq!(audit.ticks()[0].0, Some(2));
+}
+
+#[test]
+fn events_before_the_first_tick_are_grouped_under_none() {
+    let mut audit = BehaviorTreeAudit::new();
+    audit.mark(&"setup".to_string());
+    record_tick(&mut audit, "a");
+    let ticks = audit.ticks();
+    assert_eq!(ticks.len(), 2);
+    assert_eq!(
+        (ticks[0].0, rendered(&ticks[0].1)),
+        (None, vec!["mark setup".to_string()])
+    );
+    assert_eq!(ticks[1].0, Some(1));
+    assert_eq!(ticks[1].1.len(), 3);
+}
+
+#[test]
+fn drain_takes_the_events_and_keeps_counting_ticks() {
+    let mut audit = BehaviorTreeAudit::bounded(BehaviorTreeAuditLimit::Ticks(2));
+    record_tick(&mut audit, "a");
+    record_tick(&mut audit, "b");
+    let drained = audit.drain();
+    assert_eq!(drained.len(), 8);
+    assert!(matches!(drained[0], BehaviorTreeMarker::Tick(1)));
+    assert!(audit.is_empty());
+    assert_eq!(audit.limit(), BehaviorTreeAuditLimit::Ticks(2));
+
+    // The drained ticks no longer count against the limit.
+    record_tick(&mut audit, "c");
+    record_tick(&mut audit, "d");
+    assert_eq!(audit.len(), 8);
+    assert_eq!(audit.dropped_events(), 0);
+    assert_eq!(audit.ticks()[0].0, Some(3));
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+const AGENTS: u32 = 13;
+
+// Agents differ in their scripts, so they finish, fail and run out of gas on different ticks.
+fn agent_def(id: u32) -> Def {
+    let script = match id % 3 {
+        0 => vec![Complete],
+        1 => vec![Waiting, Failed],
+        _ => vec![Failed, Complete, Waiting],
+    };
+    bt! {
+        sequence {
+            repeat(id as usize % 4 + 1) { leaf(Scripted::new(id, &script)) }
+            selector { leaf(Scripted::new(id + 100, &script)) leaf(Scripted::completes(id + 200)) }
+        }
+    }
+}
+
+// What each agent returned every tick, with its leaves' log, gas left and audit length.
+type Outcome = Vec<(Vec<BehaviorTreeState>, Log, Option<i32>, usize)>;
+
+fn run(threads: usize, ticks: usize) -> Outcome {
+    let mut trees: Vec<_> = (0..AGENTS).map(|id| agent_def(id).create_tree()).collect();
+    let mut logs: Vec<Log> = (0..AGENTS).map(|_| Log::new()).collect();
+    let mut gas: Vec<Option<i32>> = (0..AGENTS).map(|id| Some(id as i32 * 2)).collect();
+    let mut audits: Vec<BehaviorTreeAudit> =
+        (0..AGENTS).map(|_| BehaviorTreeAudit::new()).collect();
+    let mut states: Vec<Vec<BehaviorTreeState>> = vec![Vec::new(); AGENTS as usize];
+    for _ in 0..ticks {
+        let mut agents: Vec<_> = trees
+            .iter_mut()
+            .zip(logs.iter_mut())
+            .zip(gas.iter().zip(audits.iter()))
+            .map(|((tree, log), (gas, audit))| {
+                BehaviorTreeBatchAgent::new(tree.as_mut(), &(), log, *gas).with_audit(audit.clone())
+            })
+            .collect();
+        let ticked = tick_batch(&mut agents, threads);
+        for (index, agent) in agents.into_iter().enumerate() {
+            gas[index] = agent.gas.map(|gas| gas + 3);
+            audits[index] = agent.audit.unwrap();
+        }
+        for ((tree, agent_states), state) in trees.iter_mut().zip(states.iter_mut()).zip(ticked) {
+            if matches!(state, Complete | Failed) {
+                tree.reset(&());
+            }
+            agent_states.push(state);
+        }
+    }
+    states
+        .into_iter()
+        .zip(logs)
+        .zip(gas.into_iter().zip(audits))
+        .map(|((states, log), (gas, audit))| (states, log, gas, audit.len()))
+        .collect()
+}
+
+#[test]
+fn results_dont_depend_on_the_thread_count() {
+    let expected = run(1, 6);
+    // The agents really do differ, or this would prove little.
+    assert!(expected
+        .iter()
+        .any(|(states, ..)| states.contains(&WaitingForGas)));
+    assert!(expected.iter().any(|(states, ..)| states.contains(&Failed)));
+    assert!(expected
+        .iter()
+        .any(|(states, ..)| states.contains(&Complete)));
+    for threads in [0, 2, 3, 4, AGENTS as usize, 64] {
+        assert!(run(threads, 6) == expected, "with {} threads", threads);
+    }
+}
+
+#[test]
+fn states_are_in_the_order_of_the_agents() {
+    let mut trees: Vec<_> = (0..4)
+        .map(|id| {
+            Def::User(Scripted::new(id, &[[Complete, Failed][id as usize % 2]])).create_tree()
+        })
+        .collect();
+    let mut logs: Vec<Log> = vec![Log::new(); 4];
+    let mut agents: Vec<_> = trees
+        .iter_mut()
+        .zip(logs.iter_mut())
+        .map(|(tree, log)| BehaviorTreeBatchAgent::new(tree.as_mut(), &(), log, None))
+        .collect();
+    assert_eq!(
+        tick_batch(&mut agents, 3),
+        [Complete, Failed, Complete, Failed]
+    );
+    assert_eq!(logs[3], [(3, Failed)]);
+}
+
+#[test]
+fn empty_batches_return_nothing() {
+    let mut agents: Vec<BehaviorTreeBatchAgent<(), Log>> = Vec::new();
+    assert_eq!(tick_batch(&mut agents, 4), []);
+}
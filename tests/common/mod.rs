@@ -0,0 +1,269 @@
+// Leaves and wrappers shared by the integration tests. Not every test uses all of them.
+#![allow(dead_code)]
+
+use behavior_bark::powered::*;
+use behavior_bark::unpowered::{UnpoweredFunction, UnpoweredFunctionState, UnpoweredTreeDef};
+
+use BehaviorTreeState::*;
+
+// Every resume of a leaf, as (leaf id, returned state), in call order.
+pub type Log = Vec<(u32, BehaviorTreeState)>;
+
+// A leaf that returns the states of its script in turn, carrying on from where it was and
+// starting over when it runs out. Resets leave its place in the script alone. Unless made
+// quiet, it audits its own entries and exits. Leaves with empty scripts fail to create.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scripted {
+    pub id: u32,
+    pub script: Vec<BehaviorTreeState>,
+    pub quiet: bool,
+}
+
+impl Scripted {
+    pub fn new(id: u32, script: &[BehaviorTreeState]) -> Self {
+        Scripted {
+            id,
+            script: script.to_vec(),
+            quiet: false,
+        }
+    }
+
+    pub fn quiet(self) -> Self {
+        Scripted {
+            quiet: true,
+            ..self
+        }
+    }
+
+    pub fn completes(id: u32) -> Self {
+        Self::new(id, &[Complete])
+    }
+
+    pub fn fails(id: u32) -> Self {
+        Self::new(id, &[Failed])
+    }
+}
+
+pub struct ScriptedNode {
+    name: String,
+    id: u32,
+    script: Vec<BehaviorTreeState>,
+    step: usize,
+    quiet: bool,
+}
+
+impl BehaviorTree for ScriptedNode {
+    type Model = ();
+    type Controller = Log;
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn resume_with(
+        &mut self,
+        _model: &(),
+        log: &mut Log,
+        _gas: &mut Option<i32>,
+        audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        let mut audit = if self.quiet { &mut None } else { audit };
+        audit.enter(&self.name);
+        let state = self.script[self.step % self.script.len()];
+        self.step += 1;
+        log.push((self.id, state));
+        audit.exit(&self.name, state);
+        state
+    }
+
+    fn reset(&mut self, _model: &()) {}
+}
+
+impl UserNodeDefinition for Scripted {
+    type Model = ();
+    type Controller = Log;
+    type Gas = Option<i32>;
+
+    fn create_node(&self) -> BoxedBehaviorTreeFor<Self> {
+        self.try_create_node().unwrap()
+    }
+
+    fn try_create_node(&self) -> Result<BoxedBehaviorTreeFor<Self>, BehaviorTreeNodeError> {
+        if self.script.is_empty() {
+            return Err("the script is empty".into());
+        }
+        Ok(Box::new(ScriptedNode {
+            name: get_bt_id(),
+            id: self.id,
+            script: self.script.clone(),
+            step: 0,
+            quiet: self.quiet,
+        }))
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+// Wrappers that run their nodes in order, like a Sequence. Hiding ones don't give them out.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum InOrder {
+    Showing,
+    Hiding,
+}
+
+pub struct InOrderNode {
+    name: String,
+    nodes: Vec<BoxedBehaviorTreeFor<Scripted>>,
+    index: usize,
+    hiding: bool,
+}
+
+impl BehaviorTree for InOrderNode {
+    type Model = ();
+    type Controller = Log;
+
+    fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    fn resume_with(
+        &mut self,
+        model: &(),
+        log: &mut Log,
+        gas: &mut Option<i32>,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        audit.enter(&self.name);
+        while let Some(node) = self.nodes.get_mut(self.index) {
+            match node.resume_with(model, log, gas, audit) {
+                Complete => self.index += 1,
+                state => {
+                    if state == Failed {
+                        self.index = 0;
+                    }
+                    audit.exit(&self.name, state);
+                    return state;
+                }
+            }
+        }
+        self.index = 0;
+        audit.exit(&self.name, Complete);
+        Complete
+    }
+
+    fn reset(&mut self, _model: &()) {
+        self.index = 0;
+    }
+
+    fn children(&self) -> &[BoxedBehaviorTreeFor<Scripted>] {
+        if self.hiding {
+            &[]
+        } else {
+            &self.nodes
+        }
+    }
+
+    fn children_mut(&mut self) -> &mut [BoxedBehaviorTreeFor<Scripted>] {
+        if self.hiding {
+            &mut []
+        } else {
+            &mut self.nodes
+        }
+    }
+}
+
+impl UserWrapperDefinition<Scripted> for InOrder {
+    fn create_node_and_wrap(
+        &self,
+        nodes: Vec<BoxedBehaviorTreeFor<Scripted>>,
+    ) -> BoxedBehaviorTreeFor<Scripted> {
+        Box::new(InOrderNode {
+            name: get_bt_id(),
+            nodes,
+            index: 0,
+            hiding: *self == InOrder::Hiding,
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+pub type Def = BehaviorTreeDef<Scripted, InOrder>;
+
+// Resumes the tree once per tick, resetting it whenever it finishes as the runners do, and
+// returns the state of every tick along with the leaves' log.
+pub fn run(def: &Def, ticks: usize) -> (Vec<BehaviorTreeState>, Log) {
+    run_with_gas_costs(def, &BehaviorTreeGasCosts::default(), ticks)
+}
+
+pub fn run_with_gas_costs(
+    def: &Def,
+    gas_costs: &BehaviorTreeGasCosts,
+    ticks: usize,
+) -> (Vec<BehaviorTreeState>, Log) {
+    let mut tree = def.create_tree_with_gas_costs(gas_costs);
+    let mut log = Log::new();
+    let mut states = Vec::new();
+    for _ in 0..ticks {
+        let state = tree.resume_with(&(), &mut log, &mut None, &mut None);
+        if matches!(state, Complete | Failed) {
+            tree.reset(&());
+        }
+        states.push(state);
+    }
+    (states, log)
+}
+
+// The unpowered counterpart of Scripted, logging into the same Log. It never audits, and
+// empty scripts always fail.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnpoweredScripted {
+    pub id: u32,
+    pub script: Vec<UnpoweredFunctionState>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    step: usize,
+}
+
+impl UnpoweredScripted {
+    pub fn new(id: u32, script: &[UnpoweredFunctionState]) -> Self {
+        UnpoweredScripted {
+            id,
+            script: script.to_vec(),
+            step: 0,
+        }
+    }
+
+    pub fn completes(id: u32) -> Self {
+        Self::new(id, &[UnpoweredFunctionState::Complete])
+    }
+
+    pub fn fails(id: u32) -> Self {
+        Self::new(id, &[UnpoweredFunctionState::Failed])
+    }
+}
+
+impl UnpoweredFunction for UnpoweredScripted {
+    type Model = ();
+    type Controller = Log;
+
+    fn resume_with(&mut self, _model: &(), log: &mut Log) -> UnpoweredFunctionState {
+        let state = match self.script.get(self.step % self.script.len().max(1)) {
+            Some(state) => *state,
+            None => UnpoweredFunctionState::Failed,
+        };
+        self.step += 1;
+        log.push((self.id, state.into()));
+        state
+    }
+
+    fn reset(&mut self, _model: &()) {}
+}
+
+pub type UnpoweredDef = UnpoweredTreeDef<UnpoweredScripted, ()>;
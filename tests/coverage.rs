@@ -0,0 +1,85 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+// The coverage of three ticks of the tree created from def.
+fn covered(def: &Def, create: fn(&Def) -> BoxedBehaviorTreeFor<Scripted>) -> BehaviorTreeCoverage {
+    let mut tree = create(def);
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    let mut coverage = BehaviorTreeCoverage::new();
+    for _ in 0..3 {
+        let mut audit = Some(BehaviorTreeAudit::new());
+        audit.as_mut().unwrap().start_tick();
+        let state = tree.resume_with(&(), &mut Log::new(), &mut None, &mut audit);
+        if matches!(state, Complete | Failed) {
+            tree.reset(&());
+        }
+        coverage.record(&index, audit.as_ref().unwrap());
+    }
+    coverage
+}
+
+// A condition guarding an action, with a fallback. None of the leaves audit themselves.
+fn guarded() -> Def {
+    bt! {
+        selector {
+            sequence {
+                leaf(Scripted::completes(1).quiet())
+                leaf(Scripted::new(2, &[Complete, Failed]).quiet())
+            }
+            leaf(Scripted::completes(3).quiet())
+        }
+    }
+}
+
+#[test]
+fn leaves_that_dont_audit_are_missed_by_plain_trees() {
+    let def = guarded();
+    let coverage = covered(&def, |def| def.create_tree());
+    assert_eq!(coverage.node(&[0]).entries, 3);
+    assert_eq!(coverage.node(&[0, 0]).entries, 0);
+}
+
+#[test]
+fn audited_trees_cover_leaves_that_dont_audit() {
+    let def = guarded();
+    let coverage = covered(&def, |def| def.create_audited_tree());
+    let report = coverage.report(&def);
+    assert_eq!(report.ratio(), 1.0);
+
+    let condition = coverage.node(&[0, 0]);
+    assert_eq!((condition.entries, condition.complete), (3, 3));
+    assert_eq!(coverage.node(&[0, 1]).failed, 1);
+    assert_eq!(coverage.node(&[1]).entries, 1);
+    assert_eq!(
+        report.gaps(),
+        [
+            (vec![], BehaviorTreeCoverageGap::NeverFailed),
+            (vec![0, 0], BehaviorTreeCoverageGap::NeverFailed),
+            (vec![1], BehaviorTreeCoverageGap::NeverFailed),
+        ]
+    );
+}
+
+#[test]
+fn audited_trees_count_leaves_that_audit_once() {
+    let def: Def = bt! {
+        sequence { leaf(Scripted::completes(1)) leaf(Scripted::fails(2)) }
+    };
+    let coverage = covered(&def, |def| def.create_audited_tree());
+    assert_eq!(coverage.node(&[0]).entries, 3);
+    assert_eq!(coverage.node(&[1]).failed, 3);
+}
+
+#[test]
+fn merged_coverage_adds_up() {
+    let def = guarded();
+    let mut coverage = covered(&def, |def| def.create_audited_tree());
+    coverage.merge(&covered(&def, |def| def.create_audited_tree()));
+    assert_eq!(coverage.node(&[0, 0]).entries, 6);
+    assert_eq!(coverage.node(&[0, 1]).failed, 2);
+}
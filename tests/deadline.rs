@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use behavior_bark::powered::*;
+
+// Completes every time, taking a millisecond of the clock it's given as the model.
+struct Slow(String);
+
+impl BehaviorTree<Deadline<ManualClock>> for Slow {
+    type Model = ManualClock;
+    type Controller = u32;
+
+    fn get_name(&self) -> &String {
+        &self.0
+    }
+
+    fn resume_with(
+        &mut self,
+        clock: &ManualClock,
+        runs: &mut u32,
+        _gas: &mut Deadline<ManualClock>,
+        _audit: &mut Option<BehaviorTreeAudit>,
+    ) -> BehaviorTreeState {
+        clock.advance(Duration::from_millis(1));
+        *runs += 1;
+        BehaviorTreeState::Complete
+    }
+
+    fn reset(&mut self, _clock: &ManualClock) {}
+}
+
+type Node = BoxedBehaviorTree<Deadline<ManualClock>, ManualClock, u32>;
+
+fn slow_leaves(count: usize) -> Vec<Node> {
+    (0..count)
+        .map(|index| Box::new(Slow(index.to_string())) as Node)
+        .collect()
+}
+
+#[test]
+fn free_sequence_stops_at_the_deadline() {
+    let clock = ManualClock::new();
+    let mut tree = Sequence::new(slow_leaves(100));
+    let mut runs = 0;
+
+    let mut gas = Deadline::after_with_clock(Duration::from_millis(5), clock.clone());
+    let state = tree.resume_with(&clock, &mut runs, &mut gas, &mut None);
+    assert_eq!(state, BehaviorTreeState::WaitingForGas);
+    assert_eq!(runs, 5);
+
+    // The next deadline picks up where the last one stopped.
+    let mut gas = Deadline::after_with_clock(Duration::from_millis(200), clock.clone());
+    let state = tree.resume_with(&clock, &mut runs, &mut gas, &mut None);
+    assert_eq!(state, BehaviorTreeState::Complete);
+    assert_eq!(runs, 100);
+}
+
+#[test]
+fn free_repeat_stops_at_the_deadline() {
+    let clock = ManualClock::new();
+    let mut tree = Repeat::new(slow_leaves(1).pop().unwrap(), 100);
+    let mut runs = 0;
+
+    let mut gas = Deadline::after_with_clock(Duration::from_millis(3), clock.clone());
+    let state = tree.resume_with(&clock, &mut runs, &mut gas, &mut None);
+    assert_eq!(state, BehaviorTreeState::WaitingForGas);
+    assert_eq!(runs, 3);
+}
+
+#[test]
+fn free_nodes_ignore_counted_budgets() {
+    assert!(0u32.has_gas_for_free_nodes());
+    assert!(Some(0).has_gas_for_free_nodes());
+
+    let clock = ManualClock::new();
+    let mut combined = (
+        0u32,
+        Deadline::after_with_clock(Duration::from_millis(1), clock.clone()),
+    );
+    assert!(combined.has_gas_for_free_nodes());
+    clock.advance(Duration::from_millis(1));
+    assert!(!combined.has_gas_for_free_nodes());
+    assert!(!combined.try_consume(0));
+}
+
+#[test]
+fn runners_reset_to_deadlines_from_the_start_of_each_tick() {
+    let clock = ManualClock::new();
+    let tree: Node = Box::new(Sequence::new(slow_leaves(100)));
+    let mut runner = TreeRunner::new(
+        tree,
+        Deadline::after_with_clock(Duration::ZERO, clock.clone()),
+    )
+    .with_gas_refill(GasRefill::Reset(Deadline::after_with_clock(
+        Duration::from_millis(5),
+        clock.clone(),
+    )));
+    let mut runs = 0;
+    for tick in 1..=3 {
+        let state = runner.tick(&clock, &mut runs);
+        assert_eq!(state, BehaviorTreeState::WaitingForGas);
+        assert_eq!(runs, tick * 5);
+        // Time passes between ticks too.
+        clock.advance(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn runners_reset_to_fixed_deadlines_unchanged() {
+    let clock = ManualClock::new();
+    let deadline = Deadline::with_clock(clock.now() + Duration::from_millis(5), clock.clone());
+    let tree: Node = Box::new(Sequence::new(slow_leaves(100)));
+    let mut runner =
+        TreeRunner::new(tree, deadline.clone()).with_gas_refill(GasRefill::Reset(deadline));
+    let mut runs = 0;
+    assert_eq!(
+        runner.tick(&clock, &mut runs),
+        BehaviorTreeState::WaitingForGas
+    );
+    assert_eq!(runs, 5);
+    assert_eq!(
+        runner.tick(&clock, &mut runs),
+        BehaviorTreeState::WaitingForGas
+    );
+    assert_eq!(runs, 5);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+impl NodeLabel for Scripted {
+    fn label(&self) -> String {
+        format!("leaf \"{}\"", self.id)
+    }
+}
+
+impl NodeLabel for InOrder {
+    fn label(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+// The leaf 3 waits, so leaf 4 is never reached in the first tick.
+fn small() -> Def {
+    bt! {
+        sequence {
+            selector { leaf(Scripted::fails(1)) leaf(Scripted::completes(2)) }
+            repeat(2) { leaf(Scripted::new(3, &[Waiting])) }
+            wrapper(InOrder::Showing) { leaf(Scripted::completes(4)) }
+        }
+    }
+}
+
+#[test]
+fn nodes_are_named_by_path_and_linked_to_their_children() {
+    assert_eq!(
+        small().to_dot(),
+        r#"digraph behavior_tree {
+  ordering=out;
+  node [shape=box, fontname="Helvetica"];
+  n [label="Sequence"];
+  n -> n_0;
+  n_0 [label="Selector"];
+  n_0 -> n_0_0;
+  n_0_0 [label="leaf \"1\"", shape=ellipse];
+  n_0 -> n_0_1;
+  n_0_1 [label="leaf \"2\"", shape=ellipse];
+  n -> n_1;
+  n_1 [label="Repeat (2)"];
+  n_1 -> n_1_0;
+  n_1_0 [label="leaf \"3\"", shape=ellipse];
+  n -> n_2;
+  n_2 [label="Wrapper: Showing"];
+  n_2 -> n_2_0;
+  n_2_0 [label="leaf \"4\"", shape=ellipse];
+}
+"#
+    );
+}
+
+#[test]
+fn overlays_fill_nodes_with_their_last_outcome() {
+    let def = small();
+    let mut tree = def.create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    let mut audit = Some(BehaviorTreeAudit::new());
+    audit.as_mut().unwrap().start_tick();
+    let state = tree.resume_with(&(), &mut Log::new(), &mut None, &mut audit);
+    assert_eq!(state, Waiting);
+    let overlay = BehaviorTreeDotOverlay::for_audit(audit.as_ref().unwrap(), &index);
+    let dot = def.to_dot_with_overlay(&overlay);
+    let lines: Vec<&str> = dot
+        .lines()
+        .filter(|line| line.contains("[label="))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            r#"  n [label="Sequence", style=filled, fillcolor=khaki];"#,
+            r#"  n_0 [label="Selector", style=filled, fillcolor=palegreen];"#,
+            r#"  n_0_0 [label="leaf \"1\"", shape=ellipse, style=filled, fillcolor=lightcoral];"#,
+            r#"  n_0_1 [label="leaf \"2\"", shape=ellipse, style=filled, fillcolor=palegreen];"#,
+            r#"  n_1 [label="Repeat (2)", style=filled, fillcolor=khaki];"#,
+            r#"  n_1_0 [label="leaf \"3\"", shape=ellipse, style=filled, fillcolor=khaki];"#,
+            r#"  n_2 [label="Wrapper: Showing"];"#,
+            r#"  n_2_0 [label="leaf \"4\"", shape=ellipse];"#,
+        ]
+    );
+}
+
+#[test]
+fn overlays_show_waiting_for_gas() {
+    let def = small();
+    let mut tree = def.create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    let mut audit = Some(BehaviorTreeAudit::new());
+    audit.as_mut().unwrap().start_tick();
+    // The Sequence and the Selector can pay to enter their first children, but not the leaf
+    // under the Selector's second.
+    let state = tree.resume_with(&(), &mut Log::new(), &mut Some(2), &mut audit);
+    assert_eq!(state, WaitingForGas);
+    let overlay = BehaviorTreeDotOverlay::for_tick(audit.as_ref().unwrap(), &index, 1);
+    assert_eq!(overlay.outcome(&[]), Some(WaitingForGas));
+    assert_eq!(overlay.outcome(&[0]), Some(WaitingForGas));
+    assert_eq!(overlay.outcome(&[0, 0]), Some(Failed));
+    assert_eq!(overlay.outcome(&[0, 1]), None);
+    assert!(def
+        .to_dot_with_overlay(&overlay)
+        .contains(r#"  n_0 [label="Selector", style=filled, fillcolor=lightblue];"#));
+}
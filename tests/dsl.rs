@@ -0,0 +1,171 @@
+#![cfg(feature = "dsl")]
+
+mod common;
+
+use behavior_bark::powered::*;
+use behavior_bark::{bt, unpowered_bt};
+
+use common::*;
+use BehaviorTreeState::*;
+
+fn every_kind() -> Def {
+    bt! {
+        sequence {
+            selector { leaf(Scripted::fails(1)) leaf(Scripted::new(2, &[Waiting, Complete])) }
+            repeat(3) { inverter { leaf(Scripted::fails(3).quiet()) } }
+            repeat(0) { succeeder { leaf(Scripted::completes(4)) } }
+            repeat_until_success { failer { leaf(Scripted::completes(5)) } }
+            repeat_until_fail { leaf(Scripted::fails(6)) }
+            wrapper(InOrder::Hiding) {
+                leaf(Scripted::completes(7))
+                wrapper(InOrder::Showing) { }
+            }
+            gas_cost(2) { gas_budget(10) { leaf(Scripted::completes(8)) } }
+            sequence { }
+        }
+    }
+}
+
+// Where the error is and what it says.
+fn error_at(text: &str) -> (usize, usize, String) {
+    let error = Def::from_dsl(text)
+        .err()
+        .expect("the text should not parse");
+    (error.line, error.column, error.message)
+}
+
+#[test]
+fn definitions_read_back_unchanged() {
+    let def = every_kind();
+    let text = def.to_dsl().unwrap();
+    assert!(Def::from_dsl(&text).unwrap() == def, "{}", text);
+}
+
+#[test]
+fn repeat_counts_and_wrappers_are_written_as_arguments() {
+    let text = every_kind().to_dsl().unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "sequence:");
+    assert_eq!(lines[4], "  repeat 3:");
+    assert!(lines.contains(&"  repeat 0:"));
+    assert!(lines.contains(&"  wrapper Hiding:"));
+    assert!(lines.contains(&"    wrapper Showing:"));
+    assert!(lines.contains(&"  gas_cost 2:"));
+    assert!(lines.contains(&"    gas_budget 10:"));
+}
+
+#[test]
+fn hand_written_text_reads_as_the_same_tree() {
+    let text = "
+# Comments and blank lines are skipped.
+selector:
+
+  (id: 1, script: [Failed], quiet: false)
+  repeat 2:
+    (id: 2, script: [Complete], quiet: true)
+";
+    let def: Def = bt! {
+        selector {
+            leaf(Scripted::fails(1))
+            repeat(2) { leaf(Scripted::completes(2).quiet()) }
+        }
+    };
+    assert!(Def::from_dsl(text).unwrap() == def);
+}
+
+#[test]
+fn unpowered_definitions_read_back_unchanged() {
+    let def: UnpoweredDef = unpowered_bt! {
+        executor {
+            repeat(4) { leaf(UnpoweredScripted::completes(1)) }
+            selector { inverter { leaf(UnpoweredScripted::fails(2)) } }
+            repeat_until_fail { failer { leaf(UnpoweredScripted::completes(3)) } }
+            wrapper(()) { }
+        }
+    };
+    let text = def.to_dsl().unwrap();
+    assert!(UnpoweredDef::from_dsl(&text).unwrap() == def, "{}", text);
+}
+
+#[test]
+fn indentation_errors_point_at_the_line() {
+    let text = "sequence:\n  (id: 1, script: [Failed], quiet: false)\n    selector:\n";
+    assert_eq!(error_at(text), (3, 5, "unexpected indentation".to_string()));
+    let text = "sequence:\n\tselector:\n";
+    assert_eq!(
+        error_at(text),
+        (2, 1, "indent with spaces, not tabs".to_string())
+    );
+    let text = "sequence:\nselector:\n";
+    assert_eq!(
+        error_at(text),
+        (2, 1, "expected a single root node".to_string())
+    );
+    assert_eq!(
+        error_at("\n# nothing\n"),
+        (1, 1, "expected a tree, found no nodes".to_string())
+    );
+}
+
+#[test]
+fn argument_errors_point_at_the_argument() {
+    let text = "sequence:\n  repeat three:\n    sequence:\n";
+    assert_eq!(
+        error_at(text),
+        (
+            2,
+            10,
+            "repeat expects a whole number, found 'three'".to_string()
+        )
+    );
+    let text = "sequence:\n  inverter now:\n    sequence:\n";
+    assert_eq!(
+        error_at(text),
+        (2, 12, "inverter takes no argument".to_string())
+    );
+    let text = "sequence:\n  failer\n";
+    assert_eq!(
+        error_at(text),
+        (2, 9, "expected ':' after failer".to_string())
+    );
+}
+
+#[test]
+fn node_errors_point_at_the_keyword() {
+    let text = "selector:\n  inverter:\n    sequence:\n    sequence:\n";
+    assert_eq!(
+        error_at(text),
+        (
+            2,
+            3,
+            "inverter expects exactly one child, found 2".to_string()
+        )
+    );
+    let text = "selector:\n  executor:\n";
+    assert_eq!(
+        error_at(text),
+        (
+            2,
+            3,
+            "executor is only available in unpowered trees".to_string()
+        )
+    );
+    let error = UnpoweredDef::from_dsl("executor:\n  gas_cost 1:\n    selector:\n")
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "2:3: gas_cost is only available in powered trees"
+    );
+}
+
+#[test]
+fn leaf_errors_point_into_the_leaf() {
+    let text = "sequence:
+  (id: 1, script: [Failed], quiet: false)
+  (id: 2, script: [Failed, Complet], quiet: false)
+";
+    // RON points just past the variant it doesn't know.
+    let (line, column, message) = error_at(text);
+    assert_eq!((line, column), (3, 35), "{}", message);
+}
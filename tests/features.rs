@@ -0,0 +1,167 @@
+// The same trees, checked the same way under every combination of the optional features.
+// Run each one of:
+//
+//     cargo test
+//     cargo test --no-default-features
+//     cargo test --no-default-features --features serde
+//     cargo test --no-default-features --features tracing
+//     cargo test --all-features
+mod common;
+
+use std::collections::HashSet;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+// Every node is entered in the first tick, which completes.
+fn every_kind() -> Def {
+    bt! {
+        sequence {
+            selector { leaf(Scripted::fails(1)) leaf(Scripted::completes(2)) }
+            inverter { leaf(Scripted::fails(3)) }
+            repeat(2) { leaf(Scripted::completes(4)) }
+            wrapper(InOrder::Showing) { leaf(Scripted::completes(5)) }
+        }
+    }
+}
+
+// The outcome of every node of every_kind in its first tick.
+fn first_tick_outcomes() -> Vec<(BehaviorTreePath, BehaviorTreeState)> {
+    let mut outcomes = Vec::new();
+    every_kind().visit(&mut |path, node_def| {
+        let state = match node_def {
+            BehaviorTreeDef::User(Scripted { id: 1 | 3, .. }) => Failed,
+            _ => Complete,
+        };
+        outcomes.push((path.clone(), state));
+    });
+    outcomes
+}
+
+fn node_at<'a>(
+    tree: &'a BoxedBehaviorTreeFor<Scripted>,
+    path: &[usize],
+) -> &'a BoxedBehaviorTreeFor<Scripted> {
+    path.iter()
+        .fold(tree, |node, child_index| &node.children()[*child_index])
+}
+
+fn audited_first_tick(def: &Def) -> (BoxedBehaviorTreeFor<Scripted>, BehaviorTreeAudit) {
+    let mut tree = def.create_tree();
+    let mut audit = Some(BehaviorTreeAudit::new());
+    audit.as_mut().unwrap().start_tick();
+    let state = tree.resume_with(&(), &mut Log::new(), &mut None, &mut audit);
+    assert_eq!(state, Complete);
+    (tree, audit.unwrap())
+}
+
+#[test]
+fn nodes_have_distinct_names() {
+    let def = every_kind();
+    let tree = def.create_tree();
+    let mut names = HashSet::new();
+    def.visit(&mut |path, _| {
+        let name = node_at(&tree, path).get_name();
+        assert!(!name.is_empty(), "at {:?}", path);
+        assert!(
+            names.insert(name.clone()),
+            "{} at {:?} is taken",
+            name,
+            path
+        );
+    });
+}
+
+#[test]
+fn index_locates_every_node() {
+    let def = every_kind();
+    let tree = def.create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    def.visit(&mut |path, _| {
+        let name = node_at(&tree, path).get_name();
+        assert_eq!(index.paths_of(name), std::slice::from_ref(path));
+    });
+}
+
+#[test]
+fn coverage_counts_each_node_separately() {
+    let def = every_kind();
+    let (tree, audit) = audited_first_tick(&def);
+    let mut coverage = BehaviorTreeCoverage::new();
+    coverage.record(&BehaviorTreeIndex::new(tree.as_ref()), &audit);
+    assert_eq!(coverage.report(&def).ratio(), 1.0);
+    for (path, state) in first_tick_outcomes() {
+        let node = coverage.node(&path);
+        let entries = if path == [2, 0] { 2 } else { 1 };
+        assert_eq!(node.entries, entries, "at {:?}", path);
+        assert_eq!(node.outcomes(state), entries, "at {:?}", path);
+    }
+}
+
+#[test]
+fn replay_and_overlay_place_each_outcome() {
+    let def = every_kind();
+    let (tree, audit) = audited_first_tick(&def);
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    let mut replay = BehaviorTreeReplay::new(&audit, &index);
+    let frame = replay.seek(audit.len()).clone();
+    let overlay = BehaviorTreeDotOverlay::for_tick(&audit, &index, 1);
+    for (path, state) in first_tick_outcomes() {
+        assert_eq!(frame.outcome(&path), Some(state), "at {:?}", path);
+        assert_eq!(overlay.outcome(&path), Some(state), "at {:?}", path);
+    }
+}
+
+#[test]
+fn profiler_keeps_nodes_apart() {
+    let def = every_kind();
+    let profiler = BehaviorTreeProfiler::new();
+    let mut tree = def.create_profiled_tree(&profiler);
+    let state = tree.resume_with(&(), &mut Log::new(), &mut None, &mut None);
+    assert_eq!(state, Complete);
+
+    let mut nodes = 0;
+    def.visit(&mut |_, _| nodes += 1);
+    assert_eq!(profiler.report().nodes.len(), nodes);
+    let repeated = profiler.stats(tree.children()[2].children()[0].get_name());
+    assert_eq!(repeated.map(|stats| stats.entries), Some(2));
+}
+
+#[test]
+fn ticks_play_out_the_same() {
+    let def: Def = bt! {
+        sequence {
+            leaf(Scripted::new(1, &[Waiting, Complete]))
+            selector {
+                leaf(Scripted::new(2, &[Failed, Waiting]))
+                leaf(Scripted::completes(3))
+            }
+        }
+    };
+    let (states, log) = run(&def, 4);
+    assert_eq!(states, [Waiting, Complete, Waiting, Waiting]);
+    assert_eq!(
+        log,
+        [
+            (1, Waiting),
+            (1, Complete),
+            (2, Failed),
+            (3, Complete),
+            (1, Waiting),
+            (1, Complete),
+            (2, Waiting),
+        ]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn audits_and_settings_are_serializable() {
+    fn serializable<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+    serializable::<BehaviorTreeAudit>();
+    serializable::<BehaviorTreeGasCosts>();
+    serializable::<RootPolicy>();
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+// Two leaves costing 2 each under an allowance of 3, so only one fits per resume.
+fn capped() -> Def {
+    bt! {
+        gas_budget(3) {
+            sequence {
+                gas_cost(2) { leaf(Scripted::completes(1)) }
+                gas_cost(2) { leaf(Scripted::completes(2)) }
+            }
+        }
+    }
+}
+
+fn resume(
+    tree: &mut BoxedBehaviorTreeFor<Scripted>,
+    log: &mut Log,
+    gas: &mut Option<i32>,
+) -> BehaviorTreeState {
+    tree.resume_with(&(), log, gas, &mut None)
+}
+
+#[test]
+fn running_out_of_allowance_waits_for_gas_while_outer_gas_remains() {
+    let mut tree = capped().create_tree_with_gas_costs(&BehaviorTreeGasCosts::free());
+    let mut log = Log::new();
+    let mut gas = Some(10);
+    assert_eq!(resume(&mut tree, &mut log, &mut gas), WaitingForGas);
+    assert_eq!(log, [(1, Complete)]);
+    // Only what was spent is gone; the unspent 1 of the allowance came back.
+    assert_eq!(gas, Some(8));
+    assert_eq!(resume(&mut tree, &mut log, &mut gas), Complete);
+    assert_eq!(log, [(1, Complete), (2, Complete)]);
+    assert_eq!(gas, Some(6));
+}
+
+#[test]
+fn allowances_cap_unlimited_gas_too() {
+    let mut tree = capped().create_tree_with_gas_costs(&BehaviorTreeGasCosts::free());
+    let mut log = Log::new();
+    let mut gas = None;
+    assert_eq!(resume(&mut tree, &mut log, &mut gas), WaitingForGas);
+    assert_eq!(resume(&mut tree, &mut log, &mut gas), Complete);
+    assert_eq!(log, [(1, Complete), (2, Complete)]);
+    assert_eq!(gas, None);
+}
+
+#[test]
+fn unspent_allowances_are_refunded() {
+    let def: Def = bt! { gas_budget(10) { gas_cost(2) { leaf(Scripted::completes(1)) } } };
+    let mut tree = def.create_tree_with_gas_costs(&BehaviorTreeGasCosts::free());
+    let mut gas = Some(5);
+    assert_eq!(resume(&mut tree, &mut Log::new(), &mut gas), Complete);
+    assert_eq!(gas, Some(3));
+}
+
+#[test]
+fn allowances_are_no_more_than_the_outer_gas() {
+    let mut tree = capped().create_tree_with_gas_costs(&BehaviorTreeGasCosts::free());
+    let mut log = Log::new();
+    let mut gas = Some(1);
+    assert_eq!(resume(&mut tree, &mut log, &mut gas), WaitingForGas);
+    assert_eq!(log, []);
+    assert_eq!(gas, Some(1));
+}
+
+#[test]
+fn budgets_charge_nothing_themselves_but_their_children_do() {
+    let mut tree = capped().create_tree();
+    let mut log = Log::new();
+    let mut gas = Some(10);
+    // The Sequence charges 1 and the first GasCost 2 of the 3, leaving nothing for the leaf
+    // under the second.
+    assert_eq!(resume(&mut tree, &mut log, &mut gas), WaitingForGas);
+    assert_eq!(log, [(1, Complete)]);
+    assert_eq!(gas, Some(7));
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+// Composites nested three deep over a single leaf.
+fn deep() -> Def {
+    bt! {
+        sequence { selector { sequence { leaf(Scripted::completes(1)) } } }
+    }
+}
+
+fn resume(
+    tree: &mut BoxedBehaviorTreeFor<Scripted>,
+    log: &mut Log,
+    gas: i32,
+) -> (BehaviorTreeState, Option<i32>) {
+    let mut gas = Some(gas);
+    let state = tree.resume_with(&(), log, &mut gas, &mut None);
+    (state, gas)
+}
+
+#[test]
+fn built_in_nodes_charge_by_default() {
+    let mut tree = deep().create_tree();
+    let mut log = Log::new();
+    assert_eq!(resume(&mut tree, &mut log, 10), (Complete, Some(7)));
+    assert_eq!(log, [(1, Complete)]);
+}
+
+#[test]
+fn free_built_in_nodes_charge_nothing() {
+    let mut tree = deep().create_tree_with_gas_costs(&BehaviorTreeGasCosts::free());
+    let mut log = Log::new();
+    assert_eq!(resume(&mut tree, &mut log, 0), (Complete, Some(0)));
+    assert_eq!(log, [(1, Complete)]);
+}
+
+#[test]
+fn built_in_nodes_wait_for_gas_and_resume_where_they_stopped() {
+    let def: Def = bt! {
+        sequence {
+            leaf(Scripted::completes(1))
+            inverter { leaf(Scripted::fails(2)) }
+            leaf(Scripted::completes(3))
+        }
+    };
+    let mut tree = def.create_tree_with_gas_costs(&BehaviorTreeGasCosts::uniform(2));
+    let mut log = Log::new();
+
+    // Enough to enter the first leaf, but not the inverter.
+    assert_eq!(resume(&mut tree, &mut log, 3), (WaitingForGas, Some(1)));
+    assert_eq!(log, [(1, Complete)]);
+
+    // Enough for the sequence to resume the inverter, but not for the inverter's child.
+    assert_eq!(resume(&mut tree, &mut log, 3), (WaitingForGas, Some(1)));
+    assert_eq!(log, [(1, Complete)]);
+
+    assert_eq!(resume(&mut tree, &mut log, 6), (Complete, Some(0)));
+    assert_eq!(log, [(1, Complete), (2, Failed), (3, Complete)]);
+}
+
+#[test]
+fn costs_can_differ_by_kind() {
+    let gas_costs = BehaviorTreeGasCosts {
+        selector: 5,
+        ..BehaviorTreeGasCosts::free()
+    };
+    let mut tree = deep().create_tree_with_gas_costs(&gas_costs);
+    assert_eq!(resume(&mut tree, &mut Log::new(), 10), (Complete, Some(5)));
+    assert_eq!(gas_costs.of(&deep()), 0);
+    assert_eq!(gas_costs.of(&deep().children()[0]), 5);
+}
+
+#[test]
+fn outcome_analysis_follows_the_costs() {
+    let def: Def = bt! { sequence { leaf(Scripted::completes(1)) } };
+    let finishes = |_: &Scripted| BehaviorTreeOutcomes::finishes();
+    let no_wrappers = |_: &InOrder, _: &[BehaviorTreeOutcomes]| BehaviorTreeOutcomes::any();
+
+    let charged = def.analyze_outcomes(&finishes, &no_wrappers);
+    assert!(charged.get(&[]).unwrap().outcomes.wait_for_gas);
+    let free =
+        def.analyze_outcomes_with_gas_costs(&BehaviorTreeGasCosts::free(), &finishes, &no_wrappers);
+    assert!(!free.get(&[]).unwrap().outcomes.wait_for_gas);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn missing_costs_deserialize_to_the_default() {
+    let gas_costs: BehaviorTreeGasCosts = serde_json::from_str(r#"{"selector": 4}"#).unwrap();
+    assert_eq!(
+        gas_costs,
+        BehaviorTreeGasCosts {
+            selector: 4,
+            ..BehaviorTreeGasCosts::default()
+        }
+    );
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+// Leaves return what their script holds; wrappers run their nodes in order, like a Sequence.
+fn analyze(def: &Def, gas_costs: &BehaviorTreeGasCosts) -> BehaviorTreeOutcomeAnalysis {
+    def.analyze_outcomes_with_gas_costs(
+        gas_costs,
+        &|leaf| BehaviorTreeOutcomes {
+            complete: leaf.script.contains(&Complete),
+            fail: leaf.script.contains(&Failed),
+            wait: leaf.script.contains(&Waiting),
+            wait_for_gas: leaf.script.contains(&WaitingForGas),
+        },
+        &|_, children| BehaviorTreeOutcomes {
+            complete: children.iter().all(|child| child.complete),
+            fail: children.iter().any(|child| child.fail),
+            wait: children.iter().any(|child| child.wait),
+            wait_for_gas: children.iter().any(|child| child.wait_for_gas),
+        },
+    )
+}
+
+fn paths(nodes: Vec<&BehaviorTreeNodeOutcomes>) -> Vec<BehaviorTreePath> {
+    nodes.into_iter().map(|node| node.path.clone()).collect()
+}
+
+fn outcomes(complete: bool, fail: bool, wait: bool) -> BehaviorTreeOutcomes {
+    BehaviorTreeOutcomes {
+        complete,
+        fail,
+        wait,
+        wait_for_gas: false,
+    }
+}
+
+#[test]
+fn outcomes_follow_the_nodes() {
+    let def: Def = bt! {
+        sequence {
+            leaf(Scripted::new(1, &[Complete, Waiting]))
+            inverter { leaf(Scripted::completes(2)) }
+            succeeder { leaf(Scripted::fails(3)) }
+        }
+    };
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::free());
+    assert_eq!(
+        analysis.get(&[0]).unwrap().outcomes,
+        outcomes(true, false, true)
+    );
+    assert_eq!(
+        analysis.get(&[1]).unwrap().outcomes,
+        outcomes(false, true, false)
+    );
+    assert_eq!(
+        analysis.get(&[2]).unwrap().outcomes,
+        outcomes(true, false, false)
+    );
+    // The Inverter always fails, so the Sequence never gets to its Succeeder.
+    assert!(!analysis.get(&[2]).unwrap().reachable);
+    assert!(!analysis.get(&[2, 0]).unwrap().reachable);
+    assert_eq!(analysis.root(), outcomes(false, true, true));
+}
+
+#[test]
+fn nodes_that_enter_children_can_wait_for_gas_under_default_costs() {
+    let def: Def = bt! {
+        sequence {
+            repeat(0) { leaf(Scripted::completes(1)) }
+            leaf(Scripted::completes(2))
+        }
+    };
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::default());
+    assert!(analysis.root().wait_for_gas);
+    assert!(!analysis.get(&[0]).unwrap().outcomes.wait_for_gas);
+    assert!(!analysis.get(&[1]).unwrap().outcomes.wait_for_gas);
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::free());
+    assert!(!analysis.root().wait_for_gas);
+}
+
+#[test]
+fn dead_branches_are_the_topmost_unreachable_nodes() {
+    let def: Def = bt! {
+        selector {
+            sequence {
+                leaf(Scripted::fails(1))
+                sequence { leaf(Scripted::completes(2)) leaf(Scripted::completes(3)) }
+            }
+            leaf(Scripted::completes(4))
+            leaf(Scripted::completes(5))
+            repeat(0) { inverter { leaf(Scripted::fails(6)) } }
+        }
+    };
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::free());
+    assert_eq!(
+        paths(analysis.dead_branches()),
+        [vec![0, 1], vec![2], vec![3]]
+    );
+    assert!(!analysis.get(&[0, 1, 0]).unwrap().reachable);
+    assert!(!analysis.get(&[3, 0, 0]).unwrap().reachable);
+}
+
+#[test]
+fn selectors_that_never_fail_are_found_if_they_can_run() {
+    let def: Def = bt! {
+        sequence {
+            selector { leaf(Scripted::fails(1)) succeeder { leaf(Scripted::fails(2)) } }
+            selector {
+                leaf(Scripted::fails(3))
+                leaf(Scripted::new(4, &[Complete, Failed]))
+            }
+            selector { leaf(Scripted::completes(5)) }
+            failer { leaf(Scripted::completes(6)) }
+            selector { leaf(Scripted::completes(7)) }
+        }
+    };
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::free());
+    assert_eq!(
+        paths(analysis.selectors_that_never_fail()),
+        [vec![0], vec![2]]
+    );
+}
+
+#[test]
+fn endless_loops_are_repeats_that_never_finish_around_children_that_do() {
+    let def: Def = bt! {
+        wrapper(InOrder::Showing) {
+            repeat_until_success { leaf(Scripted::fails(1)) }
+            repeat_until_fail { leaf(Scripted::new(2, &[Complete, Waiting])) }
+            repeat_until_success { leaf(Scripted::new(3, &[Failed, Complete])) }
+            repeat_until_fail { repeat_until_success { leaf(Scripted::fails(4)) } }
+        }
+    };
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::free());
+    // The wrapper reaches every child. The outer loop at [3] never finishes either, but
+    // neither does its child.
+    assert_eq!(
+        paths(analysis.endless_loops()),
+        [vec![0], vec![1], vec![3, 0]]
+    );
+    assert!(analysis.get(&[1]).unwrap().outcomes.wait);
+}
+
+#[test]
+fn wrappers_get_their_outcomes_from_their_children() {
+    let def: Def = bt! {
+        wrapper(InOrder::Showing) {
+            leaf(Scripted::completes(1))
+            leaf(Scripted::new(2, &[Waiting]))
+        }
+    };
+    let analysis = analyze(&def, &BehaviorTreeGasCosts::free());
+    assert_eq!(analysis.root(), outcomes(false, false, true));
+    assert_eq!(
+        analysis
+            .nodes()
+            .iter()
+            .map(|node| (node.path.clone(), node.kind))
+            .collect::<Vec<_>>(),
+        [(vec![], "Wrapper"), (vec![0], "User"), (vec![1], "User")]
+    );
+}
@@ -0,0 +1,207 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+fn name_at(tree: &BoxedBehaviorTreeFor<Scripted>, path: &[usize]) -> String {
+    path.iter()
+        .fold(tree, |node, child_index| &node.children()[*child_index])
+        .get_name()
+        .clone()
+}
+
+fn names(def: &Def, tree: &BoxedBehaviorTreeFor<Scripted>) -> Vec<String> {
+    let mut names = Vec::new();
+    def.visit(&mut |path, _| names.push(name_at(tree, path)));
+    names
+}
+
+fn tick(tree: &mut BoxedBehaviorTreeFor<Scripted>, log: &mut Log) -> BehaviorTreeState {
+    let state = tree.resume_with(&(), log, &mut None, &mut None);
+    if matches!(state, Complete | Failed) {
+        tree.reset(&());
+    }
+    state
+}
+
+// Waits on its second leaf in the first tick.
+fn patrol(last: Scripted) -> Def {
+    bt! {
+        sequence {
+            leaf(Scripted::completes(1))
+            leaf(Scripted::new(2, &[Waiting, Complete]))
+            leaf(last)
+        }
+    }
+}
+
+// A tree of def, ticked into Waiting.
+fn waiting(def: &Def, log: &mut Log) -> BoxedBehaviorTreeFor<Scripted> {
+    let mut tree = def.create_tree();
+    assert_eq!(tick(&mut tree, log), Waiting);
+    tree
+}
+
+#[test]
+fn unchanged_definitions_keep_the_tree() {
+    let def = patrol(Scripted::completes(3));
+    let mut log = Log::new();
+    let mut tree = waiting(&def, &mut log);
+    let before = names(&def, &tree);
+
+    let reload = def.try_reload_tree(&def, &mut tree).unwrap();
+    assert!(reload.is_unchanged());
+    assert!(!reload.disrupts(Some(Waiting)));
+    assert_eq!(names(&def, &tree), before);
+
+    // The sequence carries on from its second leaf.
+    log.clear();
+    assert_eq!(tick(&mut tree, &mut log), Complete);
+    assert_eq!(log, [(2, Complete), (3, Complete)]);
+}
+
+#[test]
+fn changed_leaves_are_recreated_alone() {
+    let old_def = patrol(Scripted::completes(3));
+    let new_def = patrol(Scripted::fails(4));
+    let mut log = Log::new();
+    let mut tree = waiting(&old_def, &mut log);
+    let before = names(&old_def, &tree);
+
+    let reload = new_def.try_reload_tree(&old_def, &mut tree).unwrap();
+    assert_eq!(reload.recreated, [vec![2]]);
+    assert!(reload.disrupts(Some(Waiting)));
+    assert!(!reload.disrupts(Some(Complete)));
+    assert!(!reload.disrupts(None));
+    let after = names(&new_def, &tree);
+    assert_eq!(after[..3], before[..3]);
+    assert_ne!(after[3], before[3]);
+
+    log.clear();
+    assert_eq!(tick(&mut tree, &mut log), Failed);
+    assert_eq!(log, [(2, Complete), (4, Failed)]);
+}
+
+#[test]
+fn changed_repeat_counts_recreate_the_repeat_but_keep_its_child() {
+    let repeated = |repeats| -> Def {
+        bt! {
+            sequence {
+                leaf(Scripted::completes(1))
+                repeat(repeats) { leaf(Scripted::new(2, &[Complete, Waiting])) }
+            }
+        }
+    };
+    let (old_def, new_def) = (repeated(3), repeated(1));
+    let mut log = Log::new();
+    let mut tree = waiting(&old_def, &mut log);
+    let child = name_at(&tree, &[1, 0]);
+
+    let reload = new_def.try_reload_tree(&old_def, &mut tree).unwrap();
+    assert_eq!(reload.recreated, [vec![1]]);
+    assert_eq!(name_at(&tree, &[1, 0]), child);
+
+    // The sequence is still on the repeat, which starts its single run over. The leaf carries
+    // on with its script.
+    log.clear();
+    assert_eq!(tick(&mut tree, &mut log), Complete);
+    assert_eq!(log, [(2, Complete)]);
+}
+
+#[test]
+fn failed_reloads_leave_the_tree_alone() {
+    let old_def = patrol(Scripted::completes(3));
+    let new_def = patrol(Scripted::new(4, &[]));
+    let mut log = Log::new();
+    let mut tree = waiting(&old_def, &mut log);
+    let before = names(&old_def, &tree);
+
+    let error = new_def.try_reload_tree(&old_def, &mut tree).unwrap_err();
+    assert_eq!(error.path, vec![2]);
+    assert_eq!(error.kind, "User");
+    assert_eq!(names(&old_def, &tree), before);
+
+    log.clear();
+    assert_eq!(tick(&mut tree, &mut log), Complete);
+    assert_eq!(log, [(2, Complete), (3, Complete)]);
+}
+
+#[test]
+fn wrappers_that_hide_their_nodes_are_reloaded_whole() {
+    let wrapped = |last| -> Def {
+        bt! {
+            wrapper(InOrder::Hiding) {
+                leaf(Scripted::new(1, &[Waiting, Complete]))
+                leaf(last)
+            }
+        }
+    };
+    let (old_def, new_def) = (wrapped(Scripted::completes(2)), wrapped(Scripted::fails(3)));
+    let mut log = Log::new();
+    let mut tree = waiting(&old_def, &mut log);
+    let reload = new_def.try_reload_tree(&old_def, &mut tree).unwrap();
+    assert_eq!(reload.recreated, [vec![], vec![0], vec![1]]);
+
+    log.clear();
+    assert_eq!(tick(&mut tree, &mut log), Waiting);
+    assert_eq!(log, [(1, Waiting)]);
+}
+
+#[test]
+fn schedulers_report_the_agents_a_reload_disrupts() {
+    let old_def = patrol(Scripted::completes(3));
+    let new_def = patrol(Scripted::fails(4));
+    let mut scheduler = BehaviorTreeScheduler::new(SchedulingOrder::RoundRobin);
+    for handle in 0..4 {
+        scheduler.add_agent(handle, old_def.create_tree(), 1);
+    }
+    let mut logs = vec![Log::new(); 4];
+    let mut frame = |scheduler: &mut BehaviorTreeScheduler<usize, (), Log>, agents: &[usize]| {
+        scheduler.tick_frame(&mut None, |handle, resume| {
+            if agents.contains(handle) {
+                resume(&(), &mut logs[*handle]);
+            }
+        });
+    };
+    frame(&mut scheduler, &[0, 1, 2]);
+    frame(&mut scheduler, &[2]);
+    assert_eq!(scheduler.last_state(&0), Some(Waiting));
+    assert_eq!(scheduler.last_state(&2), Some(Complete));
+    assert_eq!(scheduler.last_state(&3), None);
+
+    // Agent 0 was part way through, 2 had finished and 3 hadn't started. 1 isn't reloaded.
+    let disrupted = scheduler
+        .reload_agents(&[0, 2, 3], |tree| new_def.try_reload_tree(&old_def, tree))
+        .unwrap();
+    assert_eq!(disrupted, [0]);
+
+    let unchanged = scheduler
+        .reload_agents(&[0], |tree| new_def.try_reload_tree(&new_def, tree))
+        .unwrap();
+    assert!(unchanged.is_empty());
+
+    frame(&mut scheduler, &[0, 1]);
+    assert_eq!(scheduler.last_state(&0), Some(Failed));
+    assert_eq!(scheduler.last_state(&1), Some(Complete));
+}
+
+#[test]
+fn schedulers_stop_reloading_at_the_first_error() {
+    let old_def = patrol(Scripted::completes(3));
+    let mut scheduler = BehaviorTreeScheduler::new(SchedulingOrder::RoundRobin);
+    scheduler.add_agent(0, old_def.create_tree(), 1);
+    scheduler.tick_frame(&mut None, |_, resume| resume(&(), &mut Log::new()));
+
+    let broken = patrol(Scripted::new(4, &[]));
+    let error = scheduler
+        .reload_agents(&[0], |tree| broken.try_reload_tree(&old_def, tree))
+        .unwrap_err();
+    assert_eq!(error.path, vec![2]);
+
+    let mut log = Log::new();
+    scheduler.tick_frame(&mut None, |_, resume| resume(&(), &mut log));
+    assert_eq!(log, [(2, Complete), (3, Complete)]);
+}
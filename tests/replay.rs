@@ -0,0 +1,93 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+fn def() -> Def {
+    bt! {
+        sequence {
+            leaf(Scripted::new(1, &[Waiting, Complete]))
+            selector { leaf(Scripted::fails(2)) leaf(Scripted::completes(3)) }
+        }
+    }
+}
+
+// Two ticks of def: the first waits on leaf 1, the second completes.
+fn recorded() -> (BehaviorTreeAudit, BehaviorTreeIndex) {
+    let mut tree = def().create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    let mut audit = Some(BehaviorTreeAudit::new());
+    let mut log = Log::new();
+    for expected in [Waiting, Complete] {
+        audit.as_mut().unwrap().start_tick();
+        assert_eq!(
+            tree.resume_with(&(), &mut log, &mut None, &mut audit),
+            expected
+        );
+    }
+    (audit.unwrap(), index)
+}
+
+fn check_replay(audit: &BehaviorTreeAudit, index: &BehaviorTreeIndex) {
+    let mut replay = BehaviorTreeReplay::new(audit, index);
+    assert_eq!(replay.len(), audit.len());
+
+    let frame = replay.seek_tick(1).unwrap().clone();
+    assert_eq!(frame.tick, Some(1));
+    assert!(frame.active_path.is_empty());
+    // Entering the Sequence and then leaf 1.
+    replay.step_forward();
+    replay.step_forward();
+    assert_eq!(
+        replay.frame().active_path,
+        [BehaviorTreePath::new(), vec![0]]
+    );
+    replay.step_forward();
+    assert_eq!(replay.frame().outcome(&[0]), Some(Waiting));
+    assert_eq!(replay.frame().active_path, [BehaviorTreePath::new()]);
+
+    let frame = replay.seek(audit.len()).clone();
+    assert_eq!(frame.tick, Some(2));
+    assert!(frame.active_path.is_empty());
+    for (path, state) in [
+        (vec![], Complete),
+        (vec![0], Complete),
+        (vec![1], Complete),
+        (vec![1, 0], Failed),
+        (vec![1, 1], Complete),
+    ] {
+        assert_eq!(frame.outcome(&path), Some(state), "at {:?}", path);
+    }
+
+    // Leaf 3's exit is the second to last event, before the Selector and Sequence exit.
+    assert!(replay.step_backward());
+    assert!(replay.step_backward());
+    assert_eq!(
+        replay.frame().active_path,
+        [BehaviorTreePath::new(), vec![1]]
+    );
+    let (event, path) = replay.current_event().unwrap();
+    assert!(matches!(event, BehaviorTreeMarker::Exit(_, Complete)));
+    assert_eq!(path, Some(&vec![1, 1]));
+}
+
+#[test]
+fn replays_follow_the_recorded_ticks() {
+    let (audit, index) = recorded();
+    check_replay(&audit, &index);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn saved_audits_replay_without_the_tree() {
+    let saved = {
+        let (audit, index) = recorded();
+        serde_json::to_string(&(audit, index)).unwrap()
+    };
+    let (audit, index): (BehaviorTreeAudit, BehaviorTreeIndex) =
+        serde_json::from_str(&saved).unwrap();
+    check_replay(&audit, &index);
+}
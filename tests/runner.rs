@@ -0,0 +1,169 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+use behavior_bark::unpowered::{UnpoweredFunctionState, UnpoweredTreeRunner};
+
+use common::*;
+use BehaviorTreeState::*;
+
+fn runner(script: &[BehaviorTreeState], root_policy: RootPolicy) -> TreeRunner<(), Log> {
+    let def = Def::User(Scripted::new(1, script));
+    TreeRunner::new(def.create_tree(), None).with_root_policy(root_policy)
+}
+
+fn ticks(runner: &mut TreeRunner<(), Log>, log: &mut Log, count: usize) -> Vec<BehaviorTreeState> {
+    (0..count).map(|_| runner.tick(&(), log)).collect()
+}
+
+#[test]
+fn run_once_stops_at_the_first_result() {
+    let mut runner = runner(&[Waiting, Failed, Complete], RootPolicy::RunOnce);
+    let mut log = Log::new();
+    assert_eq!(
+        ticks(&mut runner, &mut log, 4),
+        [Waiting, Failed, Failed, Failed]
+    );
+    assert!(runner.is_finished());
+    assert_eq!(runner.ticks(), 2);
+    assert_eq!(log.len(), 2);
+    runner.restart(&());
+    assert!(!runner.is_finished());
+    assert_eq!(runner.last_state(), None);
+    assert_eq!(ticks(&mut runner, &mut log, 2), [Complete, Complete]);
+    assert_eq!(runner.ticks(), 3);
+}
+
+#[test]
+fn loop_restarts_whatever_the_result() {
+    let mut runner = runner(&[Failed, Complete], RootPolicy::Loop);
+    let mut log = Log::new();
+    assert_eq!(
+        ticks(&mut runner, &mut log, 4),
+        [Failed, Complete, Failed, Complete]
+    );
+    assert!(!runner.is_finished());
+    assert_eq!(log.len(), 4);
+}
+
+#[test]
+fn restart_on_failure_stops_once_the_root_completes() {
+    let mut runner = runner(
+        &[Failed, Failed, Complete, Failed],
+        RootPolicy::RestartOnFailure,
+    );
+    let mut log = Log::new();
+    assert_eq!(
+        ticks(&mut runner, &mut log, 5),
+        [Failed, Failed, Complete, Complete, Complete]
+    );
+    assert!(runner.is_finished());
+    assert_eq!(log.len(), 3);
+}
+
+#[test]
+fn finished_roots_are_reset_before_they_start_again() {
+    let def: Def = bt! {
+        sequence { leaf(Scripted::completes(1)) leaf(Scripted::new(2, &[Failed, Complete])) }
+    };
+    let mut runner = TreeRunner::new(def.create_tree(), None);
+    let mut log = Log::new();
+    assert_eq!(ticks(&mut runner, &mut log, 2), [Failed, Complete]);
+    assert_eq!(
+        log,
+        [(1, Complete), (2, Failed), (1, Complete), (2, Complete)]
+    );
+}
+
+// Each tick of a two leaf Sequence costs 1 per leaf under the default costs.
+fn gas_runner(gas_refill: GasRefill<Option<i32>>) -> TreeRunner<(), Log> {
+    let def: Def = bt! {
+        sequence { leaf(Scripted::completes(1)) leaf(Scripted::completes(2)) }
+    };
+    TreeRunner::new(def.create_tree(), Some(3)).with_gas_refill(gas_refill)
+}
+
+#[test]
+fn kept_gas_runs_out() {
+    let mut runner = gas_runner(GasRefill::Keep);
+    let mut log = Log::new();
+    assert_eq!(ticks(&mut runner, &mut log, 2), [Complete, WaitingForGas]);
+    assert_eq!(*runner.gas(), Some(0));
+    *runner.gas_mut() = Some(1);
+    assert_eq!(ticks(&mut runner, &mut log, 1), [Complete]);
+}
+
+#[test]
+fn reset_gas_is_full_every_tick() {
+    let mut runner = gas_runner(GasRefill::Reset(Some(3)));
+    let mut log = Log::new();
+    assert_eq!(ticks(&mut runner, &mut log, 3), [Complete; 3]);
+    assert_eq!(*runner.gas(), Some(1));
+}
+
+#[test]
+fn custom_refills_see_the_tick_number() {
+    let refill: GasRefillFn<Option<i32>> = Box::new(|gas, tick| *gas = Some(tick as i32));
+    let mut runner = gas_runner(GasRefill::Custom(refill));
+    let mut log = Log::new();
+    assert_eq!(
+        ticks(&mut runner, &mut log, 3),
+        [WaitingForGas, Complete, Complete]
+    );
+    assert_eq!(*runner.gas(), Some(1));
+}
+
+#[test]
+fn audits_follow_the_audit_policy() {
+    let count = |runner: &TreeRunner<(), Log>| runner.audit().map(|audit| audit.ticks().len());
+    let mut log = Log::new();
+
+    let mut runner = runner_with_audit(AuditPolicy::Off);
+    ticks(&mut runner, &mut log, 3);
+    assert_eq!(count(&runner), None);
+
+    let mut runner = runner_with_audit(AuditPolicy::PerTick);
+    ticks(&mut runner, &mut log, 3);
+    assert_eq!(count(&runner), Some(1));
+
+    let mut runner = runner_with_audit(AuditPolicy::Retain(BehaviorTreeAuditLimit::Ticks(2)));
+    ticks(&mut runner, &mut log, 3);
+    assert_eq!(count(&runner), Some(2));
+    let taken = runner.take_audit().unwrap();
+    assert_eq!(taken.ticks().len(), 2);
+    assert!(runner.audit().unwrap().is_empty());
+}
+
+fn runner_with_audit(audit_policy: AuditPolicy) -> TreeRunner<(), Log> {
+    runner(&[Complete], RootPolicy::Loop).with_audit(audit_policy)
+}
+
+#[test]
+fn unpowered_runners_follow_the_same_policies() {
+    use UnpoweredFunctionState::{Complete, Failed, Waiting};
+
+    let script = [Waiting, Failed, Complete, Failed];
+    let expected = [
+        (
+            RootPolicy::RunOnce,
+            [Waiting, Failed, Failed, Failed, Failed],
+        ),
+        (
+            RootPolicy::Loop,
+            [Waiting, Failed, Complete, Failed, Waiting],
+        ),
+        (
+            RootPolicy::RestartOnFailure,
+            [Waiting, Failed, Complete, Complete, Complete],
+        ),
+    ];
+    for (root_policy, states) in expected {
+        let def = UnpoweredDef::User(UnpoweredScripted::new(1, &script));
+        let mut runner = UnpoweredTreeRunner::new(def.create_tree()).with_root_policy(root_policy);
+        let mut log = Log::new();
+        let ticked: Vec<UnpoweredFunctionState> =
+            (0..5).map(|_| runner.tick(&(), &mut log)).collect();
+        assert_eq!(ticked, states, "{:?}", root_policy);
+        assert_eq!(runner.is_finished(), root_policy != RootPolicy::Loop);
+    }
+}
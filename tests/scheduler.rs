@@ -0,0 +1,143 @@
+mod common;
+
+use std::collections::HashMap;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+// Each agent repeats one leaf 100 times, spending 1 gas per run under the default costs.
+fn busy(id: u32) -> BoxedBehaviorTreeFor<Scripted> {
+    let def: Def = bt! { repeat(100) { leaf(Scripted::completes(id)) } };
+    def.create_tree()
+}
+
+fn scheduler(order: SchedulingOrder, priorities: &[u32]) -> BehaviorTreeScheduler<u32, (), Log> {
+    let mut scheduler = BehaviorTreeScheduler::new(order);
+    for (handle, priority) in priorities.iter().enumerate() {
+        scheduler.add_agent(handle as u32, busy(handle as u32), *priority);
+    }
+    scheduler
+}
+
+// Runs a frame with every agent's own log, returning how many leaves each agent ran.
+fn frame(
+    scheduler: &mut BehaviorTreeScheduler<u32, (), Log>,
+    budget: i32,
+) -> (BehaviorTreeFrameReport<u32>, HashMap<u32, usize>) {
+    let mut logs: HashMap<u32, Log> = HashMap::new();
+    let report = scheduler.tick_frame(&mut Some(budget), |handle, resume| {
+        resume(&(), logs.entry(*handle).or_default())
+    });
+    let runs = logs
+        .into_iter()
+        .map(|(handle, log)| (handle, log.len()))
+        .collect();
+    (report, runs)
+}
+
+#[test]
+fn round_robin_shares_the_budget_equally() {
+    let mut scheduler = scheduler(SchedulingOrder::RoundRobin, &[1, 5, 10]);
+    let (report, runs) = frame(&mut scheduler, 30);
+    assert_eq!(report.ran, 3);
+    // Each agent's share of 10 pays for entering its leaf 10 times.
+    assert_eq!(runs, HashMap::from([(0, 10), (1, 10), (2, 10)]));
+    assert_eq!(report.starved, [0, 1, 2]);
+    // The next frame starts one agent further along.
+    let (report, _) = frame(&mut scheduler, 30);
+    assert_eq!(report.starved, [1, 2, 0]);
+}
+
+#[test]
+fn priority_shares_are_weighted_by_priority() {
+    let mut scheduler = scheduler(SchedulingOrder::Priority, &[1, 3, 6]);
+    let (report, runs) = frame(&mut scheduler, 50);
+    assert_eq!(runs, HashMap::from([(0, 5), (1, 15), (2, 30)]));
+    // Agents are run highest priority first.
+    assert_eq!(report.starved, [2, 1, 0]);
+}
+
+#[test]
+fn agents_without_a_share_starve() {
+    let mut scheduler = scheduler(SchedulingOrder::Priority, &[1, 1, 8]);
+    // The highest priority agent's share rounds up to the whole budget, leaving nothing for
+    // the others, which aren't run at all.
+    let (report, runs) = frame(&mut scheduler, 2);
+    assert_eq!(report.ran, 1);
+    assert_eq!(runs, HashMap::from([(2, 2)]));
+    assert_eq!(report.starved, [2, 0, 1]);
+    let stats = scheduler.stats(&1).unwrap();
+    assert_eq!(stats.frames_run, 0);
+    assert_eq!(stats.frames_starved, 1);
+}
+
+#[test]
+fn starvation_stats_count_frames_in_a_row() {
+    let mut scheduler = scheduler(SchedulingOrder::RoundRobin, &[1, 1]);
+    for _ in 0..3 {
+        frame(&mut scheduler, 2);
+    }
+    // Once the agents finish their repeats they no longer run out.
+    for _ in 0..200 {
+        frame(&mut scheduler, 1000);
+    }
+    frame(&mut scheduler, 0);
+    let stats = scheduler.stats(&0).unwrap();
+    assert_eq!(stats.frames_run, 203);
+    assert_eq!(stats.frames_starved, 4);
+    assert_eq!(stats.consecutive_frames_starved, 1);
+    assert_eq!(stats.max_consecutive_frames_starved, 3);
+    assert_eq!(scheduler.frames(), 204);
+}
+
+// A single free leaf, which never runs out of gas.
+fn idle(id: u32) -> BoxedBehaviorTreeFor<Scripted> {
+    Def::User(Scripted::completes(id)).create_tree()
+}
+
+#[test]
+fn starvation_reports_list_the_most_starved_first() {
+    let mut scheduler = BehaviorTreeScheduler::new(SchedulingOrder::RoundRobin);
+    scheduler.add_agent(0, idle(0), 1);
+    scheduler.add_agent(1, busy(1), 1);
+    for _ in 0..3 {
+        frame(&mut scheduler, 4);
+    }
+    let report = scheduler.starvation_report();
+    let handles: Vec<u32> = report.iter().map(|(handle, _)| *handle).collect();
+    assert_eq!(handles, [1, 0]);
+    assert_eq!(report[0].1.consecutive_frames_starved, 3);
+    assert_eq!(report[1].1.frames_starved, 0);
+}
+
+#[test]
+fn agents_left_waiting_for_gas_go_first() {
+    let mut scheduler = BehaviorTreeScheduler::new(SchedulingOrder::Priority);
+    scheduler.add_agent(0, idle(0), 9);
+    scheduler.add_agent(1, busy(1), 1);
+    let mut order = Vec::new();
+    let mut log = Log::new();
+    let mut run = |handle: &u32, resume: &mut dyn FnMut(&(), &mut Log)| {
+        order.push(*handle);
+        resume(&(), &mut log);
+    };
+    let report = scheduler.tick_frame(&mut Some(10), &mut run);
+    assert_eq!(report.starved, [1]);
+    scheduler.tick_frame(&mut Some(10), &mut run);
+    assert_eq!(order, [0, 1, 1, 0]);
+}
+
+#[test]
+fn unlimited_budgets_run_everyone_to_completion() {
+    let mut scheduler = scheduler(SchedulingOrder::RoundRobin, &[1, 1]);
+    let mut logs: HashMap<u32, Log> = HashMap::new();
+    let report = scheduler.tick_frame(&mut None, |handle, resume| {
+        resume(&(), logs.entry(*handle).or_default())
+    });
+    assert!(report.starved.is_empty());
+    assert_eq!(logs[&0].len(), 100);
+    assert_eq!(scheduler.last_state(&1), Some(Complete));
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "schema")]
+
+mod common;
+
+use behavior_bark::powered::*;
+use behavior_bark::{bt, unpowered_bt};
+use serde_json::{json, Value};
+
+use common::*;
+use BehaviorTreeState::*;
+
+fn user_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "id": { "type": "integer", "minimum": 0 },
+            "script": {
+                "type": "array",
+                "items": { "enum": ["Complete", "Failed", "Waiting", "WaitingForGas"] },
+            },
+            "quiet": { "type": "boolean" },
+        },
+        "required": ["id", "script", "quiet"],
+        "additionalProperties": false,
+    })
+}
+
+fn schema() -> Value {
+    Def::json_schema(user_schema(), json!({ "enum": ["Showing", "Hiding"] }))
+}
+
+// Checks a value against the parts of JSON Schema the generated schemas use, with $refs
+// resolved against the root.
+fn is_valid(root: &Value, schema: &Value, value: &Value) -> bool {
+    let Some(schema) = schema.as_object() else {
+        return schema.as_bool().unwrap_or(false);
+    };
+    schema
+        .iter()
+        .all(|(keyword, argument)| match keyword.as_str() {
+            "$schema" | "title" | "$defs" => true,
+            "$ref" => {
+                let name = argument.as_str().unwrap().trim_start_matches("#/$defs/");
+                is_valid(root, &root["$defs"][name], value)
+            }
+            "oneOf" => {
+                let schemas = argument.as_array().unwrap();
+                schemas
+                    .iter()
+                    .filter(|schema| is_valid(root, schema, value))
+                    .count()
+                    == 1
+            }
+            "type" => match argument.as_str().unwrap() {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "integer" => value.is_u64() || value.is_i64(),
+                "boolean" => value.is_boolean(),
+                "string" => value.is_string(),
+                "null" => value.is_null(),
+                other => panic!("unexpected type {}", other),
+            },
+            "enum" => argument.as_array().unwrap().contains(value),
+            "minimum" => value
+                .as_f64()
+                .map_or(true, |number| number >= argument.as_f64().unwrap()),
+            "maximum" => value
+                .as_f64()
+                .map_or(true, |number| number <= argument.as_f64().unwrap()),
+            "required" => argument
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|key| value.get(key.as_str().unwrap()).is_some()),
+            "properties" => value.as_object().map_or(true, |object| {
+                object.iter().all(|(key, item)| {
+                    argument
+                        .get(key)
+                        .map_or(true, |schema| is_valid(root, schema, item))
+                })
+            }),
+            "additionalProperties" => value.as_object().map_or(true, |object| {
+                let properties = &schema["properties"];
+                object.iter().all(|(key, item)| {
+                    properties.get(key).is_some() || is_valid(root, argument, item)
+                })
+            }),
+            "minItems" => value.as_array().map_or(true, |items| {
+                items.len() as u64 >= argument.as_u64().unwrap()
+            }),
+            "prefixItems" => value.as_array().map_or(true, |items| {
+                items
+                    .iter()
+                    .zip(argument.as_array().unwrap())
+                    .all(|(item, schema)| is_valid(root, schema, item))
+            }),
+            "items" => value.as_array().map_or(true, |items| {
+                let skipped = schema
+                    .get("prefixItems")
+                    .map_or(0, |prefix| prefix.as_array().unwrap().len());
+                items
+                    .iter()
+                    .skip(skipped)
+                    .all(|item| is_valid(root, argument, item))
+            }),
+            other => panic!("unexpected keyword {}", other),
+        })
+}
+
+fn valid(value: Value) -> bool {
+    let schema = schema();
+    is_valid(&schema, &schema, &value)
+}
+
+#[test]
+fn serialized_definitions_match_the_schema() {
+    let def: Def = bt! {
+        sequence {
+            selector { leaf(Scripted::fails(1)) leaf(Scripted::new(2, &[Waiting, Complete])) }
+            repeat(3) { inverter { leaf(Scripted::completes(3).quiet()) } }
+            repeat_until_success { failer { leaf(Scripted::completes(4)) } }
+            repeat_until_fail { succeeder { leaf(Scripted::fails(5)) } }
+            wrapper(InOrder::Hiding) { leaf(Scripted::completes(6)) }
+            gas_cost(2) { gas_budget(10) { sequence { } } }
+        }
+    };
+    let value = serde_json::to_value(&def).unwrap();
+    assert!(valid(value.clone()), "{}", value);
+}
+
+#[test]
+fn out_of_range_counts_are_rejected() {
+    let leaf = serde_json::to_value(Scripted::completes(1)).unwrap();
+    assert!(valid(json!({ "Repeat": [{ "User": leaf }, 0] })));
+    assert!(!valid(json!({ "Repeat": [{ "User": leaf }, -1] })));
+    assert!(!valid(json!({ "Repeat": [{ "User": leaf }, 1.5] })));
+    assert!(valid(json!({ "GasCost": [{ "User": leaf }, u32::MAX] })));
+    assert!(!valid(
+        json!({ "GasCost": [{ "User": leaf }, u32::MAX as u64 + 1] })
+    ));
+    assert!(!valid(json!({ "GasBudget": [{ "User": leaf }] })));
+    // serde agrees.
+    assert!(serde_json::from_value::<Def>(json!({ "Repeat": [{ "User": leaf }, -1] })).is_err());
+    let too_costly = json!({ "GasCost": [{ "User": leaf }, u32::MAX as u64 + 1] });
+    assert!(serde_json::from_value::<Def>(too_costly).is_err());
+}
+
+#[test]
+fn unknown_variants_are_rejected() {
+    let leaf = serde_json::to_value(Scripted::completes(1)).unwrap();
+    assert!(!valid(json!({ "Parallel": [{ "User": leaf }] })));
+    assert!(!valid(json!({ "Sequence": [], "Selector": [] })));
+    assert!(!valid(json!({ "Wrapper": ["Sometimes", []] })));
+    assert!(!valid(
+        json!({ "User": { "id": 1, "script": ["Done"], "quiet": false } })
+    ));
+    // Executor is only for unpowered trees.
+    assert!(!valid(json!({ "Executor": [] })));
+    assert!(serde_json::from_value::<Def>(json!({ "Parallel": [] })).is_err());
+    assert!(serde_json::from_value::<Def>(json!({ "Executor": [] })).is_err());
+}
+
+#[test]
+fn unpowered_schemas_take_executors_but_not_gas_nodes() {
+    let schema = UnpoweredDef::json_schema(
+        json!({
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer", "minimum": 0 },
+                "script": {
+                    "type": "array",
+                    "items": { "enum": ["Complete", "Failed", "Waiting"] },
+                },
+            },
+            "required": ["id", "script"],
+            "additionalProperties": false,
+        }),
+        json!({ "type": "null" }),
+    );
+    let def: UnpoweredDef = unpowered_bt! {
+        executor { repeat(2) { leaf(UnpoweredScripted::completes(1)) } wrapper(()) { } }
+    };
+    let value = serde_json::to_value(&def).unwrap();
+    assert!(is_valid(&schema, &schema, &value), "{}", value);
+    assert!(!is_valid(
+        &schema,
+        &schema,
+        &json!({ "GasCost": [{ "Executor": [] }, 1] })
+    ));
+}
@@ -0,0 +1,184 @@
+// Simplified trees must tick exactly like the originals: the same state every tick, and the
+// same leaves resumed in the same order. Simplifying removes built-in nodes and their gas
+// charges, so the trees are created free.
+mod common;
+
+use std::collections::HashSet;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+use BehaviorTreeState::*;
+
+const TICKS: usize = 40;
+
+fn assert_simplifies_faithfully(def: &Def) -> Vec<BehaviorTreeSimplification> {
+    let (simplified, simplifications) = def.clone().simplify();
+    let free = BehaviorTreeGasCosts::free();
+    let (states, log) = run_with_gas_costs(def, &free, TICKS);
+    let (simplified_states, simplified_log) = run_with_gas_costs(&simplified, &free, TICKS);
+    assert_eq!(states, simplified_states, "{:?}", simplifications);
+    assert_eq!(log, simplified_log, "{:?}", simplifications);
+    simplifications
+}
+
+fn rewrites(simplifications: &[BehaviorTreeSimplification]) -> Vec<BehaviorTreeRewrite> {
+    simplifications
+        .iter()
+        .map(|simplification| simplification.rewrite.clone())
+        .collect()
+}
+
+// A xorshift generator, so failures reproduce from the seed alone.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+// A random tree of built-in nodes over scripted leaves. Repeats until success or failure are
+// left out, since they can loop forever within a single resume.
+fn generate(rng: &mut Rng, depth: u32, leaves: &mut u32) -> Def {
+    let kind = if depth == 0 { 0 } else { rng.below(12) };
+    let mut children = |rng: &mut Rng, count: u64| {
+        (0..count)
+            .map(|_| generate(rng, depth - 1, leaves))
+            .collect::<Vec<_>>()
+    };
+    match kind {
+        0 | 1 => {
+            *leaves += 1;
+            let script = (0..1 + rng.below(4))
+                .map(|_| [Complete, Failed, Waiting][rng.below(3) as usize])
+                .collect::<Vec<_>>();
+            BehaviorTreeDef::User(Scripted::new(*leaves, &script))
+        }
+        2 | 3 => {
+            let count = rng.below(4);
+            BehaviorTreeDef::Sequence(children(rng, count))
+        }
+        4 | 5 => {
+            let count = rng.below(4);
+            BehaviorTreeDef::Selector(children(rng, count))
+        }
+        6 => BehaviorTreeDef::Inverter(Box::new(children(rng, 1).remove(0))),
+        7 => BehaviorTreeDef::Succeeder(Box::new(children(rng, 1).remove(0))),
+        8 => BehaviorTreeDef::Failer(Box::new(children(rng, 1).remove(0))),
+        9 | 10 => {
+            let repeats = rng.below(4) as usize;
+            BehaviorTreeDef::Repeat(Box::new(children(rng, 1).remove(0)), repeats)
+        }
+        _ => match rng.below(3) {
+            0 => BehaviorTreeDef::GasCost(Box::new(children(rng, 1).remove(0)), 1),
+            1 => BehaviorTreeDef::GasBudget(Box::new(children(rng, 1).remove(0)), 10),
+            _ => {
+                let count = rng.below(3);
+                BehaviorTreeDef::Wrapper(InOrder::Showing, children(rng, count))
+            }
+        },
+    }
+}
+
+#[test]
+fn generated_trees_simplify_faithfully() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut seen = HashSet::new();
+    for _ in 0..2000 {
+        let def = generate(&mut rng, 5, &mut 0);
+        for rewrite in rewrites(&assert_simplifies_faithfully(&def)) {
+            seen.insert(format!("{:?}", rewrite));
+        }
+    }
+    // Every rewrite should have been exercised somewhere.
+    for rewrite in [
+        BehaviorTreeRewrite::DoubleInversion,
+        BehaviorTreeRewrite::InvertedFixedOutcome,
+        BehaviorTreeRewrite::OverriddenDecorator,
+        BehaviorTreeRewrite::SingleChildComposite,
+        BehaviorTreeRewrite::NestedComposite,
+        BehaviorTreeRewrite::SingleRepeat,
+        BehaviorTreeRewrite::NestedRepeat,
+    ] {
+        assert!(seen.contains(&format!("{:?}", rewrite)), "{:?}", rewrite);
+    }
+}
+
+#[test]
+fn nested_repeats_simplify_faithfully() {
+    let def: Def = bt! {
+        repeat(2) {
+            repeat(3) {
+                leaf(Scripted::new(1, &[Complete, Waiting, Complete, Failed, Complete]))
+            }
+        }
+    };
+    let simplifications = assert_simplifies_faithfully(&def);
+    assert_eq!(
+        rewrites(&simplifications),
+        [BehaviorTreeRewrite::NestedRepeat]
+    );
+}
+
+#[test]
+fn nested_composites_with_waiting_children_simplify_faithfully() {
+    let def: Def = bt! {
+        sequence {
+            leaf(Scripted::new(1, &[Waiting, Complete]))
+            sequence {
+                leaf(Scripted::new(2, &[Complete, Waiting, Complete]))
+                leaf(Scripted::new(3, &[Waiting, Failed, Complete]))
+            }
+            leaf(Scripted::new(4, &[Waiting, Complete]))
+        }
+    };
+    let simplifications = assert_simplifies_faithfully(&def);
+    assert_eq!(
+        rewrites(&simplifications),
+        [BehaviorTreeRewrite::NestedComposite]
+    );
+
+    let def: Def = bt! {
+        selector {
+            selector {
+                leaf(Scripted::new(1, &[Failed, Waiting]))
+                leaf(Scripted::new(2, &[Waiting, Failed]))
+            }
+            leaf(Scripted::new(3, &[Waiting, Failed, Complete]))
+        }
+    };
+    let simplifications = assert_simplifies_faithfully(&def);
+    assert_eq!(
+        rewrites(&simplifications),
+        [BehaviorTreeRewrite::NestedComposite]
+    );
+}
+
+#[test]
+fn repeats_of_zero_simplify_faithfully() {
+    let def: Def = bt! {
+        sequence {
+            repeat(0) { leaf(Scripted::new(1, &[Failed, Waiting])) }
+            repeat(2) { repeat(0) { leaf(Scripted::completes(2)) } }
+            repeat(0) { repeat(3) { leaf(Scripted::new(3, &[Waiting, Complete])) } }
+            leaf(Scripted::new(4, &[Waiting, Complete]))
+        }
+    };
+    let simplifications = assert_simplifies_faithfully(&def);
+    assert_eq!(
+        rewrites(&simplifications),
+        [
+            BehaviorTreeRewrite::NestedRepeat,
+            BehaviorTreeRewrite::NestedRepeat
+        ]
+    );
+}
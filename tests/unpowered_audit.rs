@@ -0,0 +1,187 @@
+mod common;
+
+use behavior_bark::powered::*;
+use behavior_bark::unpowered::{
+    UnpoweredFunction, UnpoweredFunctionState, UnpoweredTreeDef, UnpoweredTreeRunner,
+};
+use behavior_bark::unpowered_bt;
+
+use common::*;
+
+fn rendered(audit: &BehaviorTreeAudit) -> Vec<String> {
+    audit.events().map(|event| event.to_string()).collect()
+}
+
+// An unpowered leaf that audits each resume, under its own name if it has one.
+#[derive(Clone)]
+struct Noisy {
+    name: Option<String>,
+}
+
+impl UnpoweredFunction for Noisy {
+    type Model = ();
+    type Controller = Log;
+
+    fn resume_with(&mut self, _model: &(), _log: &mut Log) -> UnpoweredFunctionState {
+        UnpoweredFunctionState::Complete
+    }
+
+    fn resume_with_audit(
+        &mut self,
+        model: &(),
+        log: &mut Log,
+        mut audit: &mut Option<BehaviorTreeAudit>,
+    ) -> UnpoweredFunctionState {
+        audit.mark(self.get_name());
+        self.resume_with(model, log)
+    }
+
+    fn reset(&mut self, _model: &()) {}
+
+    fn get_name(&self) -> &String {
+        match &self.name {
+            Some(name) => name,
+            None => &NO_NAME,
+        }
+    }
+}
+
+static NO_NAME: String = String::new();
+
+fn every_kind() -> UnpoweredDef {
+    use UnpoweredFunctionState::*;
+
+    unpowered_bt! {
+        executor {
+            selector {
+                leaf(UnpoweredScripted::fails(1))
+                leaf(UnpoweredScripted::new(2, &[Waiting, Complete]))
+            }
+            sequence { repeat(2) { inverter { leaf(UnpoweredScripted::fails(3)) } } }
+            repeat_until_success {
+                inverter { leaf(UnpoweredScripted::new(4, &[Complete, Failed])) }
+            }
+            repeat_until_fail { leaf(UnpoweredScripted::new(5, &[Complete, Failed])) }
+            failer { succeeder { leaf(UnpoweredScripted::fails(6)) } }
+        }
+    }
+}
+
+#[test]
+fn audited_resumes_run_like_plain_ones() {
+    let def = every_kind();
+    let (mut plain, mut audited) = (def.create_tree(), def.create_tree());
+    let (mut plain_log, mut audited_log) = (Log::new(), Log::new());
+    let mut audit = Some(BehaviorTreeAudit::new());
+    for _ in 0..3 {
+        let state = plain.resume_with(&(), &mut plain_log);
+        let audited_state = audited.resume_with_audit(&(), &mut audited_log, &mut audit);
+        assert_eq!(audited_state, state);
+    }
+    assert_eq!(audited_log, plain_log);
+    assert!(!audit.unwrap().is_empty());
+
+    // Without an audit to record into, nothing changes either.
+    let mut unaudited = def.create_tree();
+    let mut unaudited_log = Log::new();
+    for _ in 0..3 {
+        unaudited.resume_with_audit(&(), &mut unaudited_log, &mut None);
+    }
+    assert_eq!(unaudited_log, plain_log);
+}
+
+#[test]
+fn built_in_nodes_audit_under_their_own_names() {
+    let def: UnpoweredDef = unpowered_bt! {
+        sequence { inverter { leaf(UnpoweredScripted::fails(1)) } }
+    };
+    let mut tree = def.create_tree();
+    let mut audit = Some(BehaviorTreeAudit::new());
+    let state = tree.resume_with_audit(&(), &mut Log::new(), &mut audit);
+    assert_eq!(state, UnpoweredFunctionState::Complete);
+
+    let events = rendered(audit.as_ref().unwrap());
+    let sequence = tree.get_name();
+    assert!(!sequence.is_empty());
+    // The inverter is the node entered after the sequence. The leaf doesn't audit.
+    let inverter = events[1].trim_start_matches("enter ").to_string();
+    assert_ne!(&inverter, sequence);
+    assert_eq!(
+        events,
+        [
+            format!("enter {}", sequence),
+            format!("enter {}", inverter),
+            format!("exit {} Complete", inverter),
+            format!("exit {} Complete", sequence),
+        ]
+    );
+}
+
+#[test]
+fn leaves_without_names_share_the_empty_one() {
+    let def: UnpoweredTreeDef<Noisy, ()> = UnpoweredTreeDef::Sequence(vec![
+        UnpoweredTreeDef::User(Noisy { name: None }),
+        UnpoweredTreeDef::User(Noisy { name: None }),
+        UnpoweredTreeDef::User(Noisy {
+            name: Some(get_bt_id()),
+        }),
+    ]);
+    let mut tree = def.create_tree();
+    let mut audit = Some(BehaviorTreeAudit::new());
+    tree.resume_with_audit(&(), &mut Log::new(), &mut audit);
+    let marks: Vec<String> = rendered(audit.as_ref().unwrap())
+        .into_iter()
+        .filter(|event| event.starts_with("mark"))
+        .collect();
+    assert_eq!(marks.len(), 3);
+    assert_eq!(marks[0], marks[1]);
+    assert_ne!(marks[1], marks[2]);
+}
+
+#[test]
+fn runners_record_audits_by_policy() {
+    let runner = |audit_policy| {
+        UnpoweredTreeRunner::new(every_kind().create_tree()).with_audit(audit_policy)
+    };
+    let ticks = |runner: &mut UnpoweredTreeRunner<(), Log>| {
+        let mut log = Log::new();
+        for _ in 0..3 {
+            runner.tick(&(), &mut log);
+        }
+    };
+
+    let mut off = runner(AuditPolicy::Off);
+    ticks(&mut off);
+    assert!(off.audit().is_none());
+    assert!(off.take_audit().is_none());
+
+    let mut per_tick = runner(AuditPolicy::PerTick);
+    ticks(&mut per_tick);
+    assert_eq!(per_tick.audit().unwrap().ticks().len(), 1);
+
+    let mut retained = runner(AuditPolicy::Retain(BehaviorTreeAuditLimit::Ticks(2)));
+    ticks(&mut retained);
+    assert_eq!(retained.audit().unwrap().ticks().len(), 2);
+    let taken = retained.take_audit().unwrap();
+    assert_eq!(taken.current_tick(), 3);
+    assert!(retained.audit().unwrap().is_empty());
+}
+
+#[test]
+fn unpowered_nodes_in_powered_trees_are_opaque_to_the_index() {
+    let def: UnpoweredDef = unpowered_bt! {
+        sequence { leaf(UnpoweredScripted::completes(1)) }
+    };
+    let mut node: UnpoweredNode<(), Log> = UnpoweredNode::new(def.create_tree(), 0);
+    let index = BehaviorTreeIndex::new(&node);
+    assert_eq!(index.paths_of(node.get_name()), [BehaviorTreePath::new()]);
+
+    let mut audit = Some(BehaviorTreeAudit::new());
+    let state = node.resume_with(&(), &mut Log::new(), &mut None, &mut audit);
+    assert_eq!(state, BehaviorTreeState::Complete);
+    // The unpowered sequence is audited inside the node but can't be placed.
+    assert_eq!(
+        index.trace(audit.as_ref().unwrap().events()),
+        [Some(vec![]), None, None, Some(BehaviorTreePath::new())]
+    );
+}
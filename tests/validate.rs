@@ -0,0 +1,151 @@
+mod common;
+
+use behavior_bark::powered::*;
+use behavior_bark::{bt, unpowered_bt};
+
+use common::*;
+use BehaviorTreeProblem::*;
+
+fn problems(
+    diagnostics: Vec<BehaviorTreeDiagnostic>,
+) -> Vec<(BehaviorTreePath, &'static str, BehaviorTreeProblem)> {
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.path, diagnostic.kind, diagnostic.problem))
+        .collect()
+}
+
+#[test]
+fn sound_definitions_have_no_diagnostics() {
+    let def: Def = bt! {
+        selector {
+            sequence { leaf(Scripted::completes(1)) leaf(Scripted::fails(2)) }
+            repeat(2) { inverter { leaf(Scripted::fails(3)) } }
+            wrapper(InOrder::Showing) { leaf(Scripted::completes(4)) }
+        }
+    };
+    assert_eq!(def.validate(), []);
+}
+
+#[test]
+fn empty_composites_are_reported() {
+    let def: Def = bt! {
+        selector {
+            selector { }
+            leaf(Scripted::completes(1))
+            succeeder { sequence { } }
+        }
+    };
+    assert_eq!(
+        problems(def.validate()),
+        [
+            (vec![0], "Selector", EmptyComposite),
+            (vec![2, 0], "Sequence", EmptyComposite),
+        ]
+    );
+}
+
+#[test]
+fn zero_repeats_are_reported() {
+    let def: Def = bt! {
+        sequence {
+            repeat(0) { leaf(Scripted::completes(1)) }
+            repeat(1) { leaf(Scripted::completes(2)) }
+        }
+    };
+    assert_eq!(problems(def.validate()), [(vec![0], "Repeat", ZeroRepeats)]);
+}
+
+#[test]
+fn placeholder_wrappers_are_reported() {
+    let def: BehaviorTreeDef<Scripted, ()> = BehaviorTreeDef::sequence([
+        BehaviorTreeDef::user(Scripted::completes(1)),
+        BehaviorTreeDef::wrapper((), [BehaviorTreeDef::user(Scripted::completes(2))]),
+    ]);
+    assert_eq!(
+        problems(def.validate()),
+        [(vec![1], "Wrapper", PlaceholderWrapper)]
+    );
+}
+
+#[test]
+fn children_after_one_that_always_ends_its_parent_are_unreachable() {
+    let def: Def = bt! {
+        sequence {
+            selector {
+                leaf(Scripted::fails(1))
+                succeeder { leaf(Scripted::fails(2)) }
+                leaf(Scripted::completes(3))
+                sequence { leaf(Scripted::completes(4)) }
+            }
+            failer { leaf(Scripted::completes(5)) }
+            leaf(Scripted::completes(6))
+        }
+    };
+    assert_eq!(
+        problems(def.validate()),
+        [
+            (vec![0, 2], "User", Unreachable { cut_off_by: 1 }),
+            (vec![0, 3], "Sequence", Unreachable { cut_off_by: 1 }),
+            (vec![2], "User", Unreachable { cut_off_by: 1 }),
+        ]
+    );
+}
+
+#[test]
+fn repeats_that_can_never_stop_are_reported() {
+    let def: Def = bt! {
+        sequence {
+            repeat_until_success { failer { leaf(Scripted::completes(1)) } }
+            repeat_until_fail { inverter { failer { leaf(Scripted::completes(2)) } } }
+            repeat_until_success { leaf(Scripted::fails(3)) }
+        }
+    };
+    assert_eq!(
+        problems(def.validate()),
+        [
+            (vec![0], "RepeatUntilSuccess", EndlessRepeat),
+            (vec![1], "RepeatUntilFail", EndlessRepeat),
+        ]
+    );
+}
+
+#[test]
+fn diagnostics_describe_themselves() {
+    let def: Def =
+        bt! { selector { succeeder { leaf(Scripted::fails(1)) } leaf(Scripted::fails(2)) } };
+    let described: Vec<String> = def.validate().iter().map(|d| d.to_string()).collect();
+    assert_eq!(
+        described,
+        ["User at [1]: is unreachable, as sibling 0 always ends its parent"]
+    );
+}
+
+#[test]
+fn unpowered_definitions_get_the_same_diagnostics() {
+    // Executors don't cut their children off, so every problem shows up on its own.
+    let def: UnpoweredDef = unpowered_bt! {
+        executor {
+            sequence { }
+            executor { }
+            repeat(0) { leaf(UnpoweredScripted::completes(1)) }
+            wrapper(()) { leaf(UnpoweredScripted::completes(2)) }
+            repeat_until_fail { succeeder { leaf(UnpoweredScripted::fails(3)) } }
+            selector {
+                succeeder { leaf(UnpoweredScripted::fails(4)) }
+                leaf(UnpoweredScripted::fails(5))
+            }
+        }
+    };
+    assert_eq!(
+        problems(def.validate()),
+        [
+            (vec![0], "Sequence", EmptyComposite),
+            (vec![1], "Executor", EmptyComposite),
+            (vec![2], "Repeat", ZeroRepeats),
+            (vec![3], "Wrapper", PlaceholderWrapper),
+            (vec![4], "RepeatUntilFail", EndlessRepeat),
+            (vec![5, 1], "User", Unreachable { cut_off_by: 0 }),
+        ]
+    );
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use behavior_bark::bt;
+use behavior_bark::powered::*;
+
+use common::*;
+
+fn wrapped(wrapper: InOrder) -> Def {
+    bt! {
+        sequence {
+            leaf(Scripted::completes(1))
+            wrapper(wrapper) {
+                leaf(Scripted::completes(2))
+                inverter { leaf(Scripted::fails(3)) }
+            }
+        }
+    }
+}
+
+fn covered(def: &Def) -> BehaviorTreeCoverage {
+    let mut tree = def.create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    let mut audit = Some(BehaviorTreeAudit::new());
+    let state = tree.resume_with(&(), &mut Log::new(), &mut None, &mut audit);
+    assert_eq!(state, BehaviorTreeState::Complete);
+
+    let mut coverage = BehaviorTreeCoverage::new();
+    coverage.record(&index, audit.as_ref().unwrap());
+    coverage
+}
+
+#[test]
+fn nodes_under_wrappers_are_indexed() {
+    let def = wrapped(InOrder::Showing);
+    let tree = def.create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    def.visit(&mut |path, _| {
+        let mut node = tree.as_ref();
+        for child_index in path {
+            node = node.children()[*child_index].as_ref();
+        }
+        assert_eq!(index.paths_of(node.get_name()), std::slice::from_ref(path));
+    });
+}
+
+#[test]
+fn nodes_under_wrappers_are_covered() {
+    let def = wrapped(InOrder::Showing);
+    let coverage = covered(&def);
+    let report = coverage.report(&def);
+    assert_eq!(report.ratio(), 1.0);
+    for (path, gap) in report.gaps() {
+        assert_ne!(gap, BehaviorTreeCoverageGap::Uncovered, "at {:?}", path);
+    }
+    assert_eq!(coverage.node(&[1, 1, 0]).failed, 1);
+}
+
+#[test]
+fn wrappers_that_hide_their_nodes_are_covered_as_a_whole() {
+    let def = wrapped(InOrder::Hiding);
+    let coverage = covered(&def);
+    assert_eq!(coverage.node(&[1]).entries, 1);
+    assert_eq!(coverage.node(&[1]).complete, 1);
+    assert_eq!(coverage.node(&[1, 0]).entries, 0);
+
+    // The hidden nodes count neither for nor against the wrapper.
+    let report = coverage.report(&def);
+    assert_eq!(report.ratio(), 1.0);
+    assert!(report.gaps().iter().all(|(path, _)| path.len() < 2));
+    assert!(report.to_string().contains("(opaque)"), "{}", report);
+}
+
+#[test]
+fn markers_of_hidden_nodes_go_to_their_wrapper() {
+    let def = wrapped(InOrder::Hiding);
+    let tree = def.create_tree();
+    let index = BehaviorTreeIndex::new(tree.as_ref());
+    assert_eq!(index.paths_of(tree.children()[1].get_name()), [vec![1]]);
+
+    let mut audit = BehaviorTreeAudit::new();
+    let (wrapper, hidden) = (tree.children()[1].get_name(), "hidden".to_string());
+    audit.enter(wrapper);
+    audit.enter(&hidden);
+    audit.mark(&hidden);
+    audit.exit(&hidden, BehaviorTreeState::Complete);
+    audit.exit(wrapper, BehaviorTreeState::Complete);
+    assert_eq!(
+        index.trace(audit.events()),
+        [Some(vec![1]), None, Some(vec![1]), None, Some(vec![1])]
+    );
+}
+
+#[test]
+fn wrappers_that_hide_their_nodes_still_create_and_run() {
+    let (states, log) = run(&wrapped(InOrder::Hiding), 1);
+    assert_eq!(states, [BehaviorTreeState::Complete]);
+    assert_eq!(
+        log,
+        [
+            (1, BehaviorTreeState::Complete),
+            (2, BehaviorTreeState::Complete),
+            (3, BehaviorTreeState::Failed),
+        ]
+    );
+}